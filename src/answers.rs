@@ -0,0 +1,135 @@
+use std::{fmt::{self, Display}, fs, io::ErrorKind, path::PathBuf};
+
+use colored::{ColoredString, Colorize};
+use serde::Deserialize;
+
+use crate::solution::Answer;
+
+/// Known-correct answers for a day's input, read from `answers/dNN.toml`, or `answers/dNN/NAME.toml`
+/// for one of several named inputs:
+///
+/// ```toml
+/// part1 = 1234
+/// part2 = "ab,cd,ef"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Answers {
+    part1: Option<Expected>,
+    part2: Option<Expected>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Expected {
+    Int(i64),
+    Str(String),
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expected::Int(n) => write!(f, "{}", n),
+            Expected::Str(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// How an answer compares to the stored one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL, expected {}", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Verdict {
+    pub fn colored(&self) -> ColoredString {
+        match self {
+            Verdict::Pass => self.to_string().green(),
+            Verdict::Fail { .. } => self.to_string().red().bold(),
+            Verdict::Unknown => self.to_string().dimmed(),
+        }
+    }
+}
+
+impl Answers {
+    /// Answers known from elsewhere, like a puzzle example.
+    pub fn new(part1: Option<&str>, part2: Option<&str>) -> Answers {
+        let expected = |s: Option<&str>| s.map(|s| Expected::Str(s.to_string()));
+
+        Answers { part1: expected(part1), part2: expected(part2) }
+    }
+
+    pub fn path(day: usize, input: Option<&str>) -> PathBuf {
+        match input {
+            Some(name) => PathBuf::from(format!("answers/d{:0>2}/{}.toml", day, name)),
+            None => PathBuf::from(format!("answers/d{:0>2}.toml", day)),
+        }
+    }
+
+    /// Loads the answers of a day's input, a missing file simply means nothing is known yet.
+    pub fn load(day: usize, input: Option<&str>) -> Result<Answers, String> {
+        let path = Answers::path(day, input);
+
+        match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map_err(|e| format!("Invalid answers file {}: {}", path.display(), e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Answers::default()),
+            Err(e) => Err(format!("Could not read answers file {}: {}", path.display(), e)),
+        }
+    }
+
+    /// How `answer` compares to the stored answer of `part`. A part without an answer never passes,
+    /// whatever text the stored answer has.
+    pub fn check(&self, part: u8, answer: &Answer) -> Verdict {
+        let expected = match part {
+            1 => &self.part1,
+            _ => &self.part2,
+        };
+
+        match (expected, answer) {
+            (None, _) | (_, Answer::NoPuzzle) => Verdict::Unknown,
+            (Some(expected), Answer::Unsolved) => Verdict::Fail { expected: expected.to_string() },
+            (Some(expected), _) if expected.to_string() == answer.to_string() => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail { expected: expected.to_string() },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let answers: Answers = toml::from_str("part1 = 42\npart2 = \"co,de\"").unwrap();
+
+        assert_eq!(answers.check(1, &Answer::Int(42)), Verdict::Pass);
+        assert_eq!(answers.check(1, &Answer::Int(41)), Verdict::Fail { expected: "42".to_string() });
+        assert_eq!(answers.check(2, &"co,de".into()), Verdict::Pass);
+        assert_eq!(answers.check(2, &Answer::Unsolved), Verdict::Fail { expected: "co,de".to_string() });
+
+        // the text of an answer that is not there does not pass for it
+        let answers = Answers::new(Some("unsolved"), Some("no puzzle"));
+        assert_eq!(answers.check(1, &Answer::Unsolved), Verdict::Fail { expected: "unsolved".to_string() });
+        assert_eq!(answers.check(2, &Answer::NoPuzzle), Verdict::Unknown);
+    }
+
+    #[test]
+    fn test_unknown() {
+        let answers: Answers = toml::from_str("part1 = 42").unwrap();
+
+        assert_eq!(answers.check(2, &Answer::Int(42)), Verdict::Unknown);
+        assert!(toml::from_str::<Answers>("part3 = 1").is_err());
+    }
+}
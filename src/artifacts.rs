@@ -0,0 +1,220 @@
+//! Files the parts produce besides their answers, like pictures and diagrams. Each run keeps them
+//! in a directory of its own, next to a manifest listing what was produced by which part.
+
+use std::{fmt::{self, Display}, fs, io, path::{Path, PathBuf}, sync::Mutex, time::{SystemTime, UNIX_EPOCH}};
+
+use serde::Serialize;
+
+/// The file in a run's directory listing its artifacts.
+pub const MANIFEST: &str = "manifest.json";
+
+/// The formats of the artifacts, it decides the file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Png,
+    Svg,
+    Mermaid,
+    Dot,
+    Text,
+}
+
+impl Kind {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Kind::Png => "png",
+            Kind::Svg => "svg",
+            Kind::Mermaid => "mmd",
+            Kind::Dot => "dot",
+            Kind::Text => "txt",
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Kind::Png => "PNG",
+            Kind::Svg => "SVG",
+            Kind::Mermaid => "Mermaid",
+            Kind::Dot => "DOT",
+            Kind::Text => "text",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The part an artifact comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Scope {
+    pub day: usize,
+    /// The name of the input when it is not the day's only one, like `alice` or `example 1`.
+    pub input: Option<String>,
+    pub part: u8,
+}
+
+impl Scope {
+    /// The directory of the scope's artifacts, relative to the run's directory.
+    fn dir(&self) -> PathBuf {
+        let day = PathBuf::from(format!("d{:0>2}", self.day));
+
+        match &self.input {
+            Some(input) => day.join(input.replace(|c: char| !c.is_ascii_alphanumeric() && c != '_', "-")),
+            None => day,
+        }
+    }
+}
+
+/// A file produced by a part.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Artifact {
+    pub day: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    pub part: u8,
+    pub name: String,
+    pub kind: Kind,
+    /// Relative to the run's directory.
+    pub file: PathBuf,
+    pub bytes: u64,
+    /// Where the file is, as it was written.
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+#[derive(Serialize)]
+struct Manifest<'a> {
+    started: u64,
+    artifacts: &'a [Artifact],
+}
+
+#[derive(Debug, Default)]
+struct State {
+    dir: Option<PathBuf>,
+    artifacts: Vec<Artifact>,
+}
+
+/// Where a run keeps its artifacts: a directory under `root` named after the time the run started,
+/// only created once something is written to it.
+#[derive(Debug)]
+pub struct Store {
+    root: PathBuf,
+    started: u64,
+    state: Mutex<State>,
+}
+
+impl Store {
+    pub fn new(root: &Path) -> Store {
+        let started = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+        Store { root: root.to_path_buf(), started, state: Mutex::default() }
+    }
+
+    /// The run's directory, created on first use.
+    fn dir(&self, state: &mut State) -> Result<PathBuf, String> {
+        if let Some(dir) = &state.dir {
+            return Ok(dir.clone());
+        }
+
+        fs::create_dir_all(&self.root).map_err(|e| format!("Could not create {}: {}", self.root.display(), e))?;
+
+        // runs started within the same second each get a directory of their own
+        let stamp = timestamp(self.started);
+        for n in 1.. {
+            let dir = match n {
+                1 => self.root.join(&stamp),
+                _ => self.root.join(format!("{}-{}", stamp, n)),
+            };
+
+            match fs::create_dir(&dir) {
+                Ok(()) => {
+                    state.dir = Some(dir.clone());
+                    return Ok(dir);
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(format!("Could not create {}: {}", dir.display(), e)),
+            }
+        }
+
+        unreachable!("one of the names is free")
+    }
+
+    /// Adds the artifact `name` of `scope`, `write` gets the path to write it to. The manifest is
+    /// rewritten after every artifact so it is complete even when the run is cut short.
+    pub fn write(&self, scope: &Scope, name: &str, kind: Kind, write: impl FnOnce(&Path) -> Result<(), String>) -> Result<Artifact, String> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let dir = self.dir(&mut state)?;
+
+        let file = scope.dir().join(format!("{}.{}", name, kind.extension()));
+        let path = dir.join(&file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Could not create {}: {}", parent.display(), e))?;
+        }
+
+        write(&path).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+        let bytes = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+        let artifact = Artifact { day: scope.day, input: scope.input.clone(), part: scope.part, name: name.to_string(), kind, file, bytes, path };
+        state.artifacts.retain(|a| a.file != artifact.file);
+        state.artifacts.push(artifact.clone());
+
+        let manifest = Manifest { started: self.started, artifacts: &state.artifacts };
+        let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+        let manifest_path = dir.join(MANIFEST);
+        fs::write(&manifest_path, json + "\n").map_err(|e| format!("Could not write {}: {}", manifest_path.display(), e))?;
+
+        Ok(artifact)
+    }
+}
+
+/// A UTC time like `2024-12-14_104005`, from the seconds since the Unix epoch.
+fn timestamp(secs: u64) -> String {
+    let (days, time) = ((secs / 86400) as i64, secs % 86400);
+
+    // the civil date of a day count, after Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}_{:02}{:02}{:02}", year, month, day, time / 3600, time / 60 % 60, time % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0), "1970-01-01_000000");
+        assert_eq!(timestamp(951_782_400), "2000-02-29_000000");
+        assert_eq!(timestamp(1_734_172_805), "2024-12-14_104005");
+    }
+
+    #[test]
+    fn test_store() {
+        let root = std::env::temp_dir().join(format!("aoc-artifacts-{}", std::process::id()));
+        let store = Store::new(&root);
+        let scope = Scope { day: 7, input: Some("example 1".to_string()), part: 2 };
+
+        let artifact = store.write(&scope, "chart", Kind::Mermaid, |path| fs::write(path, "flowchart").map_err(|e| e.to_string())).unwrap();
+        assert_eq!(artifact.file, Path::new("d07/example-1/chart.mmd"));
+        assert_eq!(artifact.bytes, 9);
+        assert_eq!(fs::read_to_string(&artifact.path).unwrap(), "flowchart");
+
+        let dir = artifact.path.ancestors().nth(3).unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(dir.join(MANIFEST)).unwrap()).unwrap();
+        assert_eq!(manifest["artifacts"][0]["file"], "d07/example-1/chart.mmd");
+        assert_eq!(manifest["artifacts"][0]["kind"], "mermaid");
+
+        let failed = store.write(&scope, "broken", Kind::Png, |_| Err("no pixels".to_string()));
+        assert!(failed.unwrap_err().ends_with("no pixels"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+use std::{fmt::{self, Display}, fs, hint::black_box, path::Path, time::{Duration, Instant}};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::{context::Context, inputs::Source, params::Params, Day};
+
+/// Summary statistics over the timed iterations, in nanoseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub stddev_ns: u64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty(), "at least one sample is needed");
+
+        let mut ns: Vec<f64> = samples.iter().map(|d| d.as_nanos() as f64).collect();
+        ns.sort_by(f64::total_cmp);
+
+        let n = ns.len();
+        let median = if n.is_multiple_of(2) { (ns[n / 2 - 1] + ns[n / 2]) / 2.0 } else { ns[n / 2] };
+        let mean = ns.iter().sum::<f64>() / n as f64;
+        // sample standard deviation, a single run has no spread
+        let variance = if n > 1 { ns.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64 } else { 0.0 };
+
+        Stats {
+            min_ns: ns[0] as u64,
+            median_ns: median.round() as u64,
+            mean_ns: mean.round() as u64,
+            stddev_ns: variance.sqrt().round() as u64,
+        }
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min {:.2?}, median {:.2?}, mean {:.2?} ± {:.2?}",
+            Duration::from_nanos(self.min_ns),
+            Duration::from_nanos(self.median_ns),
+            Duration::from_nanos(self.mean_ns),
+            Duration::from_nanos(self.stddev_ns),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PartBench {
+    pub part: u8,
+    pub stats: Stats,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: usize,
+    pub parse: Stats,
+    pub parts: Vec<PartBench>,
+}
+
+/// All the days measured by one `bench` invocation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub warmup: u32,
+    pub iterations: u32,
+    pub days: Vec<DayBench>,
+}
+
+impl BenchReport {
+    pub fn write_json(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| format!("Could not serialize benchmark: {}", e))?;
+
+        fs::write(path, json + "\n").map_err(|e| format!("Could not write {}: {}", path.display(), e))
+    }
+}
+
+/// Runs `f` `warmup` times untimed, then `iterations` times timed.
+fn measure<T>(warmup: u32, iterations: u32, mut f: impl FnMut() -> T) -> Vec<Duration> {
+    for _ in 0..warmup {
+        black_box(f());
+    }
+
+    (0..iterations)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect()
+}
+
+/// Times parsing and each part separately, the parts all share a single parsed input.
+pub fn bench_day(day: &Day, part: Option<u8>, source: &Source, params: &Params, warmup: u32, iterations: u32) -> Result<DayBench, String> {
+    let input = source.read()?;
+
+    println!("Benchmarking {} ({} warm-up, {} iterations)...\n", day, warmup, iterations);
+
+    let parsed = (day.parse)(&input, params).map_err(|e| format!("Parse error: {}", e.for_day(day.num).render()))?;
+
+    let parse = Stats::from_samples(&measure(warmup, iterations, || (day.parse)(&input, params)));
+    println!("» Parse: {}\n", parse.to_string().green());
+
+    // the parts run without side effects, printing or writing files would distort the timings
+    let context = Context::headless();
+    let mut parts = Vec::new();
+    for n in Day::parts(part) {
+        let stats = Stats::from_samples(&measure(warmup, iterations, || parsed.solve(n, &context)));
+        println!("» Part {}: {}\n", n, stats.to_string().green());
+
+        parts.push(PartBench { part: n, stats });
+    }
+    println!();
+
+    Ok(DayBench { day: day.num, parse, parts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = [4, 1, 3, 2].into_iter().map(Duration::from_nanos).collect();
+        let stats = Stats::from_samples(&samples);
+
+        assert_eq!(stats.min_ns, 1);
+        assert_eq!(stats.median_ns, 3); // 2.5 rounded
+        assert_eq!(stats.mean_ns, 3);
+        assert_eq!(stats.stddev_ns, 1); // sqrt(5 / 3)
+    }
+
+    #[test]
+    fn test_stats_single_sample() {
+        let stats = Stats::from_samples(&[Duration::from_nanos(7)]);
+
+        assert_eq!(stats, Stats { min_ns: 7, median_ns: 7, mean_ns: 7, stddev_ns: 0 });
+    }
+}
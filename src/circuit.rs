@@ -0,0 +1,269 @@
+//! Boolean circuits of two-input gates, simulated gate by gate.
+
+use std::{collections::HashMap, fmt::{self, Display}, str::FromStr};
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum Operation {
+    And,
+    Or,
+    Xor,
+}
+
+impl Operation {
+    pub fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Operation::And => a & b,
+            Operation::Or => a | b,
+            Operation::Xor => a ^ b,
+        }
+    }
+}
+
+impl FromStr for Operation {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "AND" => Ok(Operation::And),
+            "OR" => Ok(Operation::Or),
+            "XOR" => Ok(Operation::Xor),
+            _ => Err(()),
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match self {
+            Operation::And => "AND",
+            Operation::Or => "OR",
+            Operation::Xor => "XOR"
+        })
+    }
+}
+
+/// A gate reading the wires `i1` and `i2` and writing `out`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Gate {
+    pub i1: String,
+    pub op: Operation,
+    pub i2: String,
+    pub out: String,
+}
+
+impl Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} -> {}", self.i1, self.op, self.i2, self.out)
+    }
+}
+
+/// The index of a wire named `prefix` followed by a bit index below 64, like `z05`.
+fn bit(wire: &str, prefix: &str) -> Option<u32> {
+    wire.strip_prefix(prefix)?.parse().ok().filter(|&bit| bit < u64::BITS)
+}
+
+/// The number made of the wires named `prefix` followed by their bit index.
+pub fn number(values: &HashMap<String, bool>, prefix: &str) -> u64 {
+    values.iter()
+        .filter(|&(_, &value)| value)
+        .filter_map(|(wire, _)| bit(wire, prefix))
+        .fold(0, |num, bit| num | 1 << bit)
+}
+
+/// A netlist ready to simulate: the wires are numbered and the gates sorted so each one comes
+/// after the gates writing its inputs.
+#[derive(Debug, Clone)]
+pub struct Circuit {
+    wires: Vec<String>,
+    /// The wires no gate writes, they are set from outside.
+    inputs: Vec<usize>,
+    /// Input, operation, input and output wire of each gate, in evaluation order.
+    gates: Vec<(usize, Operation, usize, usize)>,
+}
+
+impl Circuit {
+    /// Sorts the gates, failing when a wire has several gates writing it or the gates form a loop.
+    pub fn new(gates: &[Gate]) -> Result<Circuit, String> {
+        let mut wires = Vec::new();
+        let mut index = HashMap::new();
+        let mut id = |wire: &str| *index.entry(wire.to_string()).or_insert_with(|| {
+            wires.push(wire.to_string());
+            wires.len() - 1
+        });
+        let numbered: Vec<_> = gates.iter().map(|g| (id(&g.i1), g.op, id(&g.i2), id(&g.out))).collect();
+
+        let mut writer = vec![None; wires.len()];
+        let mut readers = vec![Vec::new(); wires.len()];
+        for (i, &(a, _, b, out)) in numbered.iter().enumerate() {
+            if writer[out].replace(i).is_some() {
+                return Err(format!("Wire {} is written by more than one gate", wires[out]));
+            }
+            readers[a].push(i);
+            readers[b].push(i);
+        }
+
+        // Kahn's algorithm, a gate is ready once all the gates writing its inputs are sorted
+        let mut pending: Vec<usize> = numbered.iter().map(|&(a, _, b, _)| [a, b].iter().filter(|&&w| writer[w].is_some()).count()).collect();
+        let mut ready: Vec<usize> = (0..numbered.len()).filter(|&i| pending[i] == 0).collect();
+        let mut order = Vec::new();
+
+        while let Some(i) = ready.pop() {
+            order.push(numbered[i]);
+            for &reader in &readers[numbered[i].3] {
+                pending[reader] -= 1;
+                if pending[reader] == 0 {
+                    ready.push(reader);
+                }
+            }
+        }
+
+        if order.len() < numbered.len() {
+            // every gate left waits on another one left, walking back through them has to come around
+            let mut gate = (0..numbered.len()).find(|&i| pending[i] > 0).unwrap();
+            let mut path: Vec<usize> = Vec::new();
+            while !path.contains(&gate) {
+                path.push(gate);
+                let (a, _, b, _) = numbered[gate];
+                gate = [a, b].into_iter().filter_map(|w| writer[w]).find(|&g| pending[g] > 0).unwrap();
+            }
+
+            let start = path.iter().position(|&g| g == gate).unwrap();
+            let mut cycle: Vec<&str> = path[start..].iter().rev().map(|&g| wires[numbered[g].3].as_str()).collect();
+
+            // start from the first wire by name, so the same loop is always named the same way
+            let first = (0..cycle.len()).min_by_key(|&i| cycle[i]).unwrap();
+            cycle.rotate_left(first);
+            cycle.push(cycle[0]);
+            return Err(format!("The gates form a loop: {}", cycle.join(" -> ")));
+        }
+
+        let inputs = (0..wires.len()).filter(|&w| writer[w].is_none()).collect();
+
+        Ok(Circuit { wires, inputs, gates: order })
+    }
+
+    /// The names of the wires that have to be set from outside.
+    pub fn inputs(&self) -> impl Iterator<Item = &str> {
+        self.inputs.iter().map(|&w| self.wires[w].as_str())
+    }
+
+    /// The number of wires named `prefix` followed by a bit index.
+    pub fn bits(&self, prefix: &str) -> usize {
+        self.wires.iter().filter(|wire| bit(wire, prefix).is_some()).count()
+    }
+
+    /// Runs the gates with the inputs `set` gives, the value of every wire by number.
+    fn run(&self, set: impl Fn(&str) -> Option<bool>) -> Result<Vec<bool>, String> {
+        let mut values = vec![false; self.wires.len()];
+        for &w in &self.inputs {
+            values[w] = set(&self.wires[w]).ok_or_else(|| format!("Wire {} is never set", self.wires[w]))?;
+        }
+
+        for &(a, op, b, out) in &self.gates {
+            values[out] = op.apply(values[a], values[b]);
+        }
+
+        Ok(values)
+    }
+
+    /// The value of every wire, given the inputs in `values`.
+    pub fn evaluate(&self, values: &HashMap<String, bool>) -> Result<HashMap<String, bool>, String> {
+        let values = self.run(|wire| values.get(wire).copied())?;
+
+        Ok(self.wires.iter().cloned().zip(values).collect())
+    }
+
+    /// The number on the `z` wires with the numbers `x` and `y` on the input wires of the same name.
+    pub fn add(&self, x: u64, y: u64) -> Result<u64, String> {
+        let set = |wire: &str| match (bit(wire, "x"), bit(wire, "y")) {
+            (Some(bit), _) => Some(x >> bit & 1 == 1),
+            (_, Some(bit)) => Some(y >> bit & 1 == 1),
+            _ => None,
+        };
+
+        let values = self.run(set)?;
+        Ok(self.wires.iter().zip(values).filter(|&(_, value)| value).filter_map(|(wire, _)| bit(wire, "z")).fold(0, |num, bit| num | 1 << bit))
+    }
+
+    /// Checks the circuit adds its `x` and `y` wires into the `z` wires: every bit on its own,
+    /// every bit on both sides so it carries, and pseudo-random numbers. The error tells the first
+    /// sum that comes out wrong.
+    pub fn check_adder(&self) -> Result<(), String> {
+        let bits = self.bits("x");
+        if bits >= u64::BITS as usize {
+            return Err(format!("An adder of {} bits is too wide to check", bits));
+        }
+        let mask = (1 << bits) - 1;
+
+        let mut cases: Vec<(u64, u64)> = (0..bits).flat_map(|i| [(1 << i, 0), (0, 1 << i), (1 << i, 1 << i)]).collect();
+        cases.extend([(mask, 1), (mask, mask)]);
+
+        // xorshift, the same numbers every run so a failure can be reproduced
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut random = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state & mask
+        };
+        cases.extend((0..64).map(|_| (random(), random())));
+
+        for (x, y) in cases {
+            let sum = self.add(x, y)?;
+            if sum != x + y {
+                return Err(format!("{} + {} gave {} instead of {}", x, y, sum, x + y));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn gates(netlist: &str) -> Vec<Gate> {
+        netlist.lines().map(|line| {
+            let (i1, op, i2, _, out) = line.split_whitespace().collect_tuple().unwrap();
+            Gate { i1: i1.to_string(), op: op.parse().unwrap(), i2: i2.to_string(), out: out.to_string() }
+        }).collect()
+    }
+
+    /// A two-bit ripple-carry adder, listed out of order.
+    const ADDER: &str = "s01 XOR c00 -> z01\nx00 XOR y00 -> z00\nx01 XOR y01 -> s01\nx00 AND y00 -> c00\nx01 AND y01 -> a01\ns01 AND c00 -> b01\na01 OR b01 -> z02";
+
+    #[test]
+    fn test_add() {
+        let circuit = Circuit::new(&gates(ADDER)).unwrap();
+
+        assert_eq!(circuit.bits("x"), 2);
+        assert_eq!(circuit.add(3, 1), Ok(4));
+        assert_eq!(circuit.add(2, 3), Ok(5));
+        assert_eq!(circuit.check_adder(), Ok(()));
+
+        let values: HashMap<String, bool> = [("x00", true), ("x01", true), ("y00", false), ("y01", true)].into_iter().map(|(w, v)| (w.to_string(), v)).collect();
+        assert_eq!(number(&circuit.evaluate(&values).unwrap(), "z"), 5);
+    }
+
+    #[test]
+    fn test_check_adder() {
+        // the carry into the second bit swapped with its sum
+        let circuit = Circuit::new(&gates(&ADDER.replace("-> c00", "-> tmp").replace("-> z00", "-> c00").replace("-> tmp", "-> z00"))).unwrap();
+
+        assert_eq!(circuit.check_adder(), Err("1 + 0 gave 2 instead of 1".to_string()));
+    }
+
+    #[test]
+    fn test_errors() {
+        let looped = gates("x00 AND abc -> def\ndef OR x01 -> ghi\nghi XOR y00 -> abc\nabc AND y01 -> z00");
+        assert_eq!(Circuit::new(&looped).unwrap_err(), "The gates form a loop: abc -> def -> ghi -> abc");
+
+        let twice = gates("x00 AND y00 -> z00\nx00 OR y00 -> z00");
+        assert_eq!(Circuit::new(&twice).unwrap_err(), "Wire z00 is written by more than one gate");
+
+        let circuit = Circuit::new(&gates("x00 AND abc -> z00")).unwrap();
+        assert_eq!(circuit.add(1, 0), Err("Wire abc is never set".to_string()));
+    }
+}
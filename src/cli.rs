@@ -0,0 +1,232 @@
+use std::{path::PathBuf, str::FromStr};
+
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+
+/// Advent of Code 2024 solutions.
+///
+/// Without any arguments an interactive menu is shown.
+#[derive(Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Show diagnostics on stderr, `-v` for debug and `-vv` for trace
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Only show the extra diagnostics of these days, e.g. `17` or `15,22`
+    #[arg(long, value_name = "DAYS", global = true)]
+    pub log_days: Option<DaySet>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Run days against their puzzle input
+    Run(RunArgs),
+    /// List the implemented days
+    List,
+    /// Time parsing and each part over several iterations
+    Bench(BenchArgs),
+    /// Compare the latest benchmark run against an earlier one
+    Compare(CompareArgs),
+    /// Check each day against the examples from its puzzle description
+    TestExamples(Selection),
+}
+
+#[derive(Args, Default)]
+pub struct Selection {
+    /// Days to run, e.g. `7`, `1..=10`, `1..10` or `3,5,20..=25`
+    pub days: Option<DaySet>,
+
+    /// Run every implemented day
+    #[arg(short, long, conflicts_with = "days")]
+    pub all: bool,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.days.is_none() && !self.all
+    }
+}
+
+/// The days to run, which parts and on what input.
+#[derive(Args, Default)]
+pub struct DayArgs {
+    #[command(flatten)]
+    pub selection: Selection,
+
+    /// Only run this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Read the input from this file, or stdin for `-`, instead of the inputs directory (single day only)
+    #[arg(short, long)]
+    pub input: Option<PathBuf>,
+
+    /// Override a puzzle parameter, e.g. `--param width=11` (repeatable)
+    #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+    pub params: Vec<(String, String)>,
+}
+
+#[derive(Args, Default)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// Run against the examples from the puzzle description and check their answers
+    #[arg(short, long, conflicts_with = "input")]
+    pub example: bool,
+
+    /// Print the results in this format instead of the coloured progress
+    #[arg(short, long, value_enum)]
+    pub format: Option<Format>,
+
+    /// Write the results to this file instead of stdout (plain unless --format is given)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Fail a part that runs longer than this many seconds, 0 waits forever [default: 120]
+    #[arg(short, long, value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+
+    /// Leave out what needs a display or a person watching: no clipboard, drawings or files
+    /// unless --artifacts is given
+    #[arg(long)]
+    pub headless: bool,
+
+    /// Directory for the files the parts write, like pictures, each run gets a folder of its own
+    /// with a manifest.json listing them [default: output]
+    #[arg(long, value_name = "DIR")]
+    pub artifacts: Option<PathBuf>,
+}
+
+/// Machine-readable result formats, none of them contains colour codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    Json,
+    Csv,
+    Plain,
+}
+
+#[derive(Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub days: DayArgs,
+
+    /// Number of timed runs per part
+    #[arg(short = 'n', long, default_value_t = 10)]
+    pub iterations: u32,
+
+    /// Number of untimed runs before measuring
+    #[arg(short, long, default_value_t = 3)]
+    pub warmup: u32,
+
+    /// Also write the results to this JSON file
+    #[arg(long)]
+    pub json: Option<PathBuf>,
+
+    /// Do not record this run in the benchmark history, a run on an --input file is never recorded
+    #[arg(long)]
+    pub no_history: bool,
+}
+
+#[derive(Args)]
+pub struct CompareArgs {
+    /// Commit (or prefix) of the baseline run, defaults to the run before the latest
+    #[arg(short, long)]
+    pub baseline: Option<String>,
+
+    /// Slowdown in percent of the median past which a part counts as regressed
+    #[arg(short, long, default_value_t = 5.0)]
+    pub threshold: f64,
+}
+
+/// A set of day numbers, kept sorted and free of duplicates.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaySet(pub Vec<usize>);
+
+impl FromStr for DaySet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut days = Vec::new();
+
+        for part in s.split(',').map(str::trim) {
+            if let Some((from, to)) = part.split_once("..") {
+                let (to, inclusive) = match to.strip_prefix('=') {
+                    Some(to) => (to, true),
+                    None => (to, false),
+                };
+                let from = parse_day(from)?;
+                // the end of `a..b` is one past the last day, so `1..26` is every day
+                let to = match parse_number(to)? {
+                    to if inclusive => to,
+                    to => to.saturating_sub(1),
+                };
+
+                if from > to {
+                    return Err(format!("empty range `{}`", part));
+                }
+                days.extend(from..=check_day(to)?);
+            } else {
+                days.push(parse_day(part)?);
+            }
+        }
+
+        days.sort();
+        days.dedup();
+
+        Ok(DaySet(days))
+    }
+}
+
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.trim().to_string())),
+        _ => Err(format!("`{}` is not like name=value", s)),
+    }
+}
+
+fn parse_day(s: &str) -> Result<usize, String> {
+    check_day(parse_number(s)?)
+}
+
+fn parse_number(s: &str) -> Result<usize, String> {
+    s.parse::<usize>().map_err(|_| format!("`{}` is not a day number", s))
+}
+
+fn check_day(day: usize) -> Result<usize, String> {
+    match day {
+        1..=25 => Ok(day),
+        _ => Err(format!("day {} is not between 1 and 25", day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_day_set() {
+        assert_eq!("7".parse(), Ok(DaySet(vec![7])));
+        assert_eq!("1..=3".parse(), Ok(DaySet(vec![1, 2, 3])));
+        assert_eq!("1..3".parse(), Ok(DaySet(vec![1, 2])));
+        assert_eq!("5,3,4..=5".parse(), Ok(DaySet(vec![3, 4, 5])));
+        assert_eq!("1..26".parse(), Ok(DaySet((1..=25).collect())));
+    }
+
+    #[test]
+    fn test_day_set_errors() {
+        assert!("99".parse::<DaySet>().is_err());
+        assert!("abc".parse::<DaySet>().is_err());
+        assert!("0".parse::<DaySet>().is_err());
+        assert!("5..5".parse::<DaySet>().is_err());
+        assert!("1..1".parse::<DaySet>().is_err());
+        assert_eq!("1..27".parse::<DaySet>(), Err("day 26 is not between 1 and 25".to_string()));
+        assert!("1..".parse::<DaySet>().is_err());
+    }
+}
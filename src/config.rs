@@ -0,0 +1,89 @@
+use std::{collections::BTreeMap, fs, io::ErrorKind, path::PathBuf};
+
+use serde::Deserialize;
+
+/// Local settings read from `aoc.toml` in the working directory, everything is optional:
+///
+/// ```toml
+/// inputs = "../inputs"
+/// user = "alice"
+/// timeout = 300
+///
+/// [fetch]
+/// session = "53616c74..."
+///
+/// [params.d18]
+/// width = 7
+/// height = 7
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Directory to read the inputs from instead of `inputs`.
+    pub inputs: Option<PathBuf>,
+    /// Subfolder of the inputs directory holding this user's inputs, so several sets can live side by side.
+    pub user: Option<String>,
+    /// Seconds a part may run before it fails, `--timeout` wins over it.
+    pub timeout: Option<u64>,
+    #[serde(default)]
+    pub fetch: FetchConfig,
+    /// Parameter overrides per day, keyed like `d18`.
+    #[serde(default)]
+    params: BTreeMap<String, toml::Table>,
+}
+
+/// How missing inputs are downloaded.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FetchConfig {
+    /// The session cookie of the puzzle website, `AOC_SESSION` wins over it.
+    pub session: Option<String>,
+    /// Where to download from instead of the puzzle website, like a local stand-in server.
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    pub fn path() -> PathBuf {
+        PathBuf::from("aoc.toml")
+    }
+
+    /// Loads the config, a missing file means the defaults.
+    pub fn load() -> Result<Config, String> {
+        let path = Config::path();
+
+        match fs::read_to_string(&path) {
+            Ok(content) => toml::from_str(&content).map_err(|e| format!("Invalid config file {}: {}", path.display(), e)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("Could not read config file {}: {}", path.display(), e)),
+        }
+    }
+
+    /// The parameters set for a day, as they would be written on the command line.
+    pub fn params(&self, day: usize) -> Vec<(String, String)> {
+        let Some(table) = self.params.get(&format!("d{:0>2}", day)) else {
+            return Vec::new();
+        };
+
+        table.iter().map(|(name, value)| {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            };
+            (name.clone(), value)
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        let config: Config = toml::from_str("[params.d18]\nwidth = 7\nname = \"small\"").unwrap();
+
+        assert_eq!(config.params(18), [("name".to_string(), "small".to_string()), ("width".to_string(), "7".to_string())]);
+        assert!(config.params(14).is_empty());
+        assert!(toml::from_str::<Config>("[parameters]").is_err());
+    }
+}
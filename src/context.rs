@@ -0,0 +1,160 @@
+use std::{fmt::Display, fs, path::Path, sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex}};
+
+use clipboard::ClipboardProvider;
+
+use crate::artifacts::{Artifact, Kind, Scope, Store};
+
+/// Where a part's output besides its answer goes.
+#[derive(Debug)]
+pub enum Output {
+    Stdout,
+    /// Used when stdout only gets the final results.
+    Stderr,
+    /// Kept in memory, for tests.
+    #[cfg(test)]
+    Captured(String),
+    Discard,
+}
+
+/// How much the parts tell about what they are doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+}
+
+/// Everything a part may use besides its input. Side effects go through it, so a headless run
+/// can leave them out instead of failing without a display or a writable working directory.
+#[derive(Debug)]
+pub struct Context {
+    output: Mutex<Output>,
+    artifacts: Option<(Arc<Store>, Scope)>,
+    produced: Mutex<Vec<Artifact>>,
+    verbosity: Verbosity,
+    headless: bool,
+    cancelled: AtomicBool,
+}
+
+impl Context {
+    pub fn new(output: Output, artifacts: Option<(Arc<Store>, Scope)>, verbosity: Verbosity, headless: bool) -> Context {
+        Context { output: Mutex::new(output), artifacts, produced: Mutex::default(), verbosity, headless, cancelled: AtomicBool::new(false) }
+    }
+
+    /// A context without any side effects, what the parts get when they are called directly.
+    pub fn headless() -> Context {
+        Context::new(Output::Discard, None, Verbosity::Quiet, true)
+    }
+
+    fn write(&self, level: Verbosity, text: impl Display) {
+        if level > self.verbosity {
+            return;
+        }
+
+        let mut output = self.output.lock().unwrap_or_else(|e| e.into_inner());
+        match &mut *output {
+            Output::Stdout => println!("{}", text),
+            Output::Stderr => eprintln!("{}", text),
+            #[cfg(test)]
+            Output::Captured(captured) => {
+                captured.push_str(&text.to_string());
+                captured.push('\n');
+            }
+            Output::Discard => {}
+        }
+    }
+
+    /// Shows something worth seeing on a normal run, like a drawing of the solution.
+    pub fn print(&self, text: impl Display) {
+        self.write(Verbosity::Normal, text);
+    }
+
+    /// Keeps `contents` as the artifact `name`, when the run keeps artifacts.
+    pub fn emit(&self, name: &str, kind: Kind, contents: impl AsRef<[u8]>) {
+        self.emit_with(name, kind, |path| fs::write(path, contents).map_err(|e| e.to_string()));
+    }
+
+    /// Keeps the artifact `name` that `write` writes to the path it gets, for files written by
+    /// another library. `write` is not called when the run keeps no artifacts.
+    pub fn emit_with(&self, name: &str, kind: Kind, write: impl FnOnce(&Path) -> Result<(), String>) {
+        let Some((store, scope)) = &self.artifacts else {
+            return tracing::debug!("{}.{} not kept, the run keeps no artifacts", name, kind.extension());
+        };
+
+        match store.write(scope, name, kind, write) {
+            Ok(artifact) => self.produced.lock().unwrap_or_else(|e| e.into_inner()).push(artifact),
+            Err(e) => tracing::warn!("{}", e),
+        }
+    }
+
+    /// Tells the part to give up, its answer is no longer awaited.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the part should give up, checked by the parts that can run for long.
+    pub fn cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The artifacts emitted so far.
+    pub fn produced(&self) -> Vec<Artifact> {
+        self.produced.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Copies `text`, described as `what`, to the clipboard unless the run is headless.
+    pub fn copy_to_clipboard(&self, what: &str, text: String) {
+        if self.headless {
+            return tracing::debug!("{} not copied, the run is headless", what);
+        }
+
+        match clipboard::ClipboardContext::new().and_then(|mut clip| clip.set_contents(text)) {
+            Ok(()) => self.print(format!("{} copied to the clipboard", what)),
+            Err(e) => self.print(format!("Could not use the clipboard: {}", e)),
+        }
+    }
+
+    /// What was printed, for a context created with `Output::Captured`.
+    #[cfg(test)]
+    pub fn captured(&self) -> String {
+        match &*self.output.lock().unwrap() {
+            Output::Captured(captured) => captured.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity() {
+        let context = Context::new(Output::Captured(String::new()), None, Verbosity::Normal, true);
+
+        context.print("shown");
+        context.copy_to_clipboard("Flowchart", "flowchart".to_string());
+        context.emit("chart", Kind::Mermaid, "flowchart");
+
+        assert_eq!(context.captured(), "shown\n");
+        assert_eq!(context.produced(), []);
+
+        assert!(!context.cancelled());
+        context.cancel();
+        assert!(context.cancelled());
+    }
+
+    #[test]
+    fn test_artifacts() {
+        let root = std::env::temp_dir().join(format!("aoc-context-{}", std::process::id()));
+        let scope = Scope { day: 24, input: None, part: 2 };
+        let context = Context::new(Output::Discard, Some((Arc::new(Store::new(&root)), scope)), Verbosity::Quiet, true);
+
+        context.emit("chart", Kind::Mermaid, "flowchart");
+
+        let produced = context.produced();
+        assert_eq!(produced.len(), 1);
+        assert_eq!(produced[0].file, Path::new("d24/chart.mmd"));
+        assert_eq!(fs::read_to_string(&produced[0].path).unwrap(), "flowchart");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,63 +1,80 @@
-fn parse_columns(input: &String) -> (Vec<i64>, Vec<i64>) {
-    let mut left = Vec::new();
-    let mut right = Vec::new();
-
-    for line in input.lines() {
-        let mut nums = line.split_whitespace().map(|n| n.parse::<i64>().unwrap());
-        left.push(nums.next().unwrap());
-        right.push(nums.next().unwrap());
-    }
+use indoc::indoc;
 
-    (left, right)
-}
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let (mut left, mut right) = parse_columns(input);
+pub struct Solver;
 
-    // sort the vectors
-    left.sort();
-    right.sort();
+impl Solution for Solver {
+    type Input = (Vec<i64>, Vec<i64>);
 
-    // zip the vectors and sum the differences
-    let sum: i64 = left.into_iter().zip(right.into_iter()).map(|(l, r)| (r - l).abs()).sum();
-    Box::new(sum)
-}
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let (left, right) = parse_columns(input);
+        for line in input.lines() {
+            let mut nums = line.split_whitespace().map(|n| n.parse::<i64>());
+            match (nums.next(), nums.next()) {
+                (Some(Ok(l)), Some(Ok(r))) => {
+                    left.push(l);
+                    right.push(r);
+                }
+                _ => return Err(ParseError::new("Invalid location ids").at(input, line).expected("two numbers")),
+            }
+        }
 
-    // for each number of left, count how many times it appears in right, multiply and sum
-    let mut sum: i64 = 0;
-    for l in left {
-        sum += l * right.iter().filter(|&r| *r == l).count() as i64;
+        Ok((left, right))
     }
 
-    Box::new(sum)
-}
+    fn part1((left, right): &Self::Input) -> Answer {
+        let mut left = left.clone();
+        let mut right = right.clone();
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+        // sort the vectors
+        left.sort();
+        right.sort();
+
+        // zip the vectors and sum the differences
+        let sum: i64 = left.into_iter().zip(right).map(|(l, r)| (r - l).abs()).sum();
+        sum.into()
+    }
+
+    fn part2((left, right): &Self::Input) -> Answer {
+        // for each number of left, count how many times it appears in right, multiply and sum
+        let mut sum: i64 = 0;
+        for &l in left {
+            sum += l * right.iter().filter(|&r| *r == l).count() as i64;
+        }
 
-    const TEST_INPUT: &str = indoc! {"
+        sum.into()
+    }
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         3   4
         4   3
         2   5
         1   3
         3   9
         3   3"
-    };
+    }).part1("11").part2("31"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 11;
     const TEST_RESULT2: i64 = 31;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&TEST_INPUT.to_string()).to_string(), TEST_RESULT1.to_string());        
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&TEST_INPUT.to_string()).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
-}
\ No newline at end of file
+}
@@ -1,66 +1,91 @@
-fn parse(input: &String) -> Vec<Vec<i64>> {
-    input
-        .lines()
-        .map(|line| line
-            .split_whitespace()
-            .filter_map(|n| n.parse().ok())
-            .collect()
-        ).collect()
-}
+use indoc::indoc;
+
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
 
-fn check(report: &Vec<i64>) -> bool {
-    let sign = (report[1] - report[0]).signum();
+fn check(report: &[i64]) -> bool {
+    // a single level is safe, part 2 gets one by removing a level from two
+    let [first, second, ..] = *report else {
+        return true;
+    };
+    let sign = (second - first).signum();
     report
         .windows(2)
         .map(|window| window[1] - window[0])
         .all(|diff| diff.signum() == sign && diff.abs() <= 3)
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let reports = parse(input);
+impl Solution for Solver {
+    type Input = Vec<Vec<i64>>;
 
-    Box::new(reports.into_iter().filter(check).count())
-}
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| {
+                let report = line
+                    .split_whitespace()
+                    .map(|n| n.parse().map_err(|_| ParseError::new(format!("{} is not a number", n)).at(input, n)))
+                    .collect::<Result<Vec<i64>, _>>()?;
+                if report.len() < 2 {
+                    return Err(ParseError::new("A report needs at least two levels").at(input, line).expected("a report like 7 6 4 2 1"));
+                }
+
+                Ok(report)
+            }).collect()
+    }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let reports = parse(input);
+    fn part1(reports: &Self::Input) -> Answer {
+        reports.iter().filter(|report| check(report)).count().into()
+    }
 
-    Box::new(reports.into_iter().filter(|report| {
-        if check(report) {
-            return true;
-        }
+    fn part2(reports: &Self::Input) -> Answer {
+        reports.iter().filter(|report| {
+            if check(report) {
+                return true;
+            }
 
-        (0..report.len()).any(|i| {
-            let mut report = report.clone();
-            report.remove(i);
-            check(&report)
-        })
-    }).count() as i64)
+            (0..report.len()).any(|i| {
+                let mut report = report.to_vec();
+                report.remove(i);
+                check(&report)
+            })
+        }).count().into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-    
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         7 6 4 2 1
         1 2 7 8 9
         9 7 6 2 1
         1 3 2 4 5
         8 6 4 4 1
         1 3 6 7 9"
-    };
+    }).part1("2").part2("4"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 2;
     const TEST_RESULT2: i64 = 4;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_parse_error() {
+        assert!(Solver::parse("7 6 4 2 1\n3").unwrap_err().to_string().contains("at least two levels"));
+        assert_eq!(Solver::part2(&Solver::parse("1 9").unwrap()), 1.into());
+    }
+}
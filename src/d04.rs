@@ -1,93 +1,62 @@
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let mut count = 0;
-    let matrix = input.lines().map(|line| line.trim().bytes().collect::<Vec<u8>>()).collect::<Vec<Vec<u8>>>();
-
-    let directions = [
-        (0, 1),
-        (1, 0),
-        (0, -1),
-        (-1, 0),
-        (1, 1),
-        (-1, -1),
-        (1, -1),
-        (-1, 1),
-    ];
-
-    const XMAS: [u8;4] = *b"XMAS";
-
-    for y in 0..matrix.len() {
-        for x in 0..matrix[y].len() {
-            if matrix[y][x] != XMAS[0] {
-                continue;
-            }
+use indoc::indoc;
 
-            'nextDir: for (dx, dy) in directions.iter() {
-                let mut mx = x as i64 + dx;
-                let mut my = y as i64 + dy;
+use crate::{error::ParseError, examples::Example, geometry::Dir8, grid::Grid, params::Params, solution::{Answer, Solution}};
 
-                for i in 1..XMAS.len() {
-                    if mx < 0 || my < 0 || my >= matrix.len() as i64 || mx >= matrix[my as usize].len() as i64 {
-                        continue 'nextDir;
-                    }
+pub struct Solver;
 
-                    if matrix[my as usize][mx as usize] != XMAS[i] {
-                        continue 'nextDir;
-                    }
+impl Solution for Solver {
+    type Input = Grid<u8>;
 
-                    mx += dx;
-                    my += dy;
-                }
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
 
-                count += 1;
+    fn part1(grid: &Self::Input) -> Answer {
+        const XMAS: [u8;4] = *b"XMAS";
+
+        let mut count = 0;
+
+        for (p, _) in grid.iter().filter(|&(_, &c)| c == XMAS[0]) {
+            // each neighbour is where the word goes on in one direction
+            for next in grid.neighbours8(p) {
+                let step = next - p;
+                let found = XMAS[1..].iter().enumerate().all(|(i, c)| {
+                    grid.get(p + step * (i as i64 + 1)) == Some(c)
+                });
+
+                if found {
+                    count += 1;
+                }
             }
         }
+
+        count.into()
     }
-    
-    
-    Box::new(count)
-}
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let mut count = 0;
-    let matrix = input.lines().map(|line| line.trim().bytes().collect::<Vec<u8>>()).collect::<Vec<Vec<u8>>>();
-
-    let corners = [
-        (-1, -1),
-        (-1, 1),
-        (1, -1),
-        (1, 1),
-    ];
-
-    for y in 1..matrix.len()-1 {
-        for x in 1..matrix[y].len()-1 {
-            if matrix[y][x] != 'A' as u8 {
-                continue;
-            }
+    fn part2(grid: &Self::Input) -> Answer {
+        let mut count = 0;
+
+        let corners = [
+            Dir8::NW,
+            Dir8::SW,
+            Dir8::NE,
+            Dir8::SE,
+        ];
 
-            let vals = corners.iter().map(|(dx, dy)| {
-                let mx = x as i64 + dx;
-                let my = y as i64 + dy;
-                
-                matrix[my as usize][mx as usize]
-            }).collect::<Vec<u8>>();
+        for (p, _) in grid.iter().filter(|&(_, &c)| c == b'A') {
+            let vals = corners.iter().map(|dir| grid.get(p + dir.vector()).copied()).collect::<Option<Vec<u8>>>();
 
-            match vals.as_slice() {
-                b"MMSS" | b"SSMM" | b"SMSM" | b"MSMS" => count += 1,
-                _ => (),
+            if let Some(b"MMSS" | b"SSMM" | b"SMSM" | b"MSMS") = vals.as_deref() {
+                count += 1;
             }
         }
-    }
-
-    Box::new(count)
 
+        count.into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-    
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         MMMSXXMASM
         MSAMXMSMSA
         AMXSXMAAMM
@@ -98,17 +67,24 @@ mod tests {
         SAXAMASAAA
         MAMMMXMMMM
         MXMXAXMASX"
-    };
+    }).part1("18").part2("9"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 18;
     const TEST_RESULT2: i64 = 9;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
 }
\ No newline at end of file
@@ -1,32 +1,34 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
-struct Input {
+use indoc::indoc;
+
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+pub struct Input {
     rules: Rules,
     manuals: Manuals,
 }
 
 type Manuals = Vec<Manual>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Manual {
     pages: Vec<usize>,
 }
 
+/// The pages that have to come after each page.
+#[derive(Default)]
 struct Rules {
-    map: Vec<Vec<usize>>,
+    map: HashMap<usize, Vec<usize>>,
 }
 
 struct RuleDef(usize, usize);
 
 impl Rules {
     fn get(&self, key: usize) -> Option<&Vec<usize>> {
-        self.map.get(key)
-    }
-}
-
-impl Default for Rules {
-    fn default() -> Self {
-        Rules { map: Vec::with_capacity(100) }
+        self.map.get(&key)
     }
 }
 
@@ -73,7 +75,7 @@ impl FromStr for RuleDef {
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         input
-            .split_once("|").ok_or(parse_error(input))
+            .split_once("|").ok_or_else(|| parse_error(input))
             .and_then(|(a, b)| {
                 let a: usize = a.parse().map_err(|_| number_parse_error(input, a))?;
                 let b: usize = b.parse().map_err(|_| number_parse_error(input, b))?;
@@ -88,18 +90,10 @@ impl FromStr for Rules {
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         let rules = input
             .lines()
-            .map(|line| line.parse())
+            .map(|line| line.parse().map_err(|e: ParseError| e.at(input, line).expected("a rule like 47|53")))
             .try_fold(Rules::default(), |mut rules, rule| {
                 let rule: RuleDef = rule?;
-                match rules.map.get_mut(rule.0) {
-                    Some(vec) => vec.push(rule.1),
-                    None => {
-                        if rules.map.len() <= rule.0 {
-                            rules.map.resize(rule.0 + 1, Vec::new());
-                        }
-                        rules.map[rule.0] = vec![rule.1];
-                    } 
-                }
+                rules.map.entry(rule.0).or_default().push(rule.1);
                 Ok(rules)
             })?;
             
@@ -133,74 +127,52 @@ impl FromStr for Input {
     
             let manuals = manualstr
                 .lines()
-                .map(|line| line.parse())
+                .map(|line| line.parse().map_err(|e: ParseError| e.at(input, line).expected("pages like 75,47,61")))
                 .collect::<Result<Manuals,_>>()?;
     
-            return Ok(Input {rules, manuals});
+            Ok(Input {rules, manuals})
         } else {
-            return Err(ParseError { message: String::from("Error parsing input") });
+            Err(ParseError::new("Expected rules and manuals separated by an empty line"))
         }
     }
 }
 
-struct ParseError {
-    message: String,
-}
+impl Solution for Solver {
+    type Input = Input;
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    Box::new(
-        match input.parse::<Input>() {
-            Ok(input) => 
-                input.manuals
-                    .into_iter()
-                    .filter(|m| m.is_valid(&input.rules))
-                    .map(|m| m.middle())
-                    .sum::<usize>()
-                    .try_into()
-                    .expect("Sum is too large for i64"),
-    
-            Err(e) => {
-                eprintln!("{}", e.message);
-                0
-            }
-        }
-    )
-}
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    Box::new(
-        match input.parse::<Input>() {
-            Ok(input) => 
-                input.manuals
-                    .into_iter()
-                    .filter(|m| !m.is_valid(&input.rules))
-                    .map(|m| m.sort(&input.rules).middle())
-                    .sum::<usize>()
-                    .try_into()
-                    .expect("Sum is too large for i64"),
-    
-            Err(e) => {
-                eprintln!("{}", e.message);
-                0
-            }
-        }
-    )
+    fn part1(input: &Self::Input) -> Answer {
+        input.manuals
+            .iter()
+            .filter(|m| m.is_valid(&input.rules))
+            .map(|m| m.middle())
+            .sum::<usize>()
+            .into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        input.manuals
+            .iter()
+            .filter(|m| !m.is_valid(&input.rules))
+            .map(|m| m.clone().sort(&input.rules).middle())
+            .sum::<usize>()
+            .into()
+    }
 }
 
 fn parse_error(input: &str) -> ParseError {
-    ParseError { message: format!("Error parsing [{}]", input) }
+    ParseError::new(format!("Error parsing [{}]", input))
 }
 
 fn number_parse_error(input: &str, nan: &str) -> ParseError {
-    ParseError { message: format!("Error parsing [{}], {} is not a number", input, nan) }
+    ParseError::new(format!("Error parsing [{}], {} is not a number", input, nan))
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-    
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         47|53
         97|13
         97|61
@@ -229,17 +201,31 @@ mod tests {
         75,97,47,61,53
         61,13,29
         97,13,75,29,47"
-    };
+    }).part1("143").part2("123"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 143;
     const TEST_RESULT2: i64 = 123;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
+    }
+
+    #[test]
+    fn test_large_pages() {
+        // page numbers are keys, a huge one needs no room for all the pages below it
+        let input = "1000000000000|1\n\n1000000000000,1,2";
+        assert_eq!(Solver::part1(&Solver::parse(input).unwrap()), 1.into());
     }
 }
\ No newline at end of file
@@ -1,82 +1,44 @@
 use std::{collections::HashSet, str::FromStr, sync::{Arc, Mutex}, thread::spawn};
 
+use indoc::indoc;
+
+use crate::{error::ParseError, examples::Example, geometry::{Direction, Point}, grid::Grid, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
 #[derive(Clone)]
-struct World {
-    map: Vec<Space>,
-    width: usize,
-    height: usize,
+pub struct World {
+    map: Grid<Space>,
     guard: Guard,
 }
 
 impl FromStr for World {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines = s.lines().collect::<Vec<&str>>();
-        let height = lines.len();
-        let width = lines[0].len();
-        let mut guard: Option<Guard> = None;
-
-        let map = lines
-            .join("")
-            .chars()
-            .enumerate()
-            .map(|(i, c)| {
-                match c {
-                    '.' => Ok(Space::Empty),
-                    '#' => Ok(Space::Obstacle),
-                    '^' | 'v' | '<' | '>' => {
-                        guard = Some(Guard::new(i % width, i / width, c.try_into()?));
-                        Ok(Space::Empty)
-                    },
-                    _ => Err(format!("Invalid space: {}", c)),
-                }
-            })
-            .collect::<Result<Vec<Space>, String>>()?;
+        let chars = Grid::parse(s, |c| match c {
+            '.' | '#' | '^' | 'v' | '<' | '>' => Ok(c),
+            _ => Err(ParseError::new(format!("Invalid space: {}", c)).expected("one of . # ^ v < >")),
+        })?;
 
-        if guard.is_none() {
-            return Err("No guard found".to_string());
-        }
+        let Some((pos, &c)) = chars.iter().find(|(_, c)| "^v<>".contains(**c)) else {
+            return Err(ParseError::new("No guard found"));
+        };
+        let guard = Guard::new(pos, c.try_into().map_err(ParseError::new)?);
 
-        Ok(Self { map, width, height, guard: guard.unwrap() })
+        let map = chars.map(|&c| if c == '#' { Space::Obstacle } else { Space::Empty });
+
+        Ok(Self { map, guard })
     }
 }
 
 impl World {
-    fn get(&self, (x, y): (i64, i64)) -> Result<&Space, WorldError> {
-        if x < 0 || y < 0 {
-            return Err(WorldError::OutOfBounds);
-        }
-        
-        let x = x as usize;
-        let y = y as usize;
-
-        if x >= self.width || y >= self.height {
-            return Err(WorldError::OutOfBounds);
-        }
-        
-        let i: usize = y * self.width + x;
-
-        Ok(&self.map[i])
+    fn get(&self, pos: Point) -> Result<&Space, WorldError> {
+        self.map.get(pos).ok_or(WorldError::OutOfBounds)
     }
 
-    fn set(&mut self, (x, y): (i64, i64), s: Space) -> Result<(), WorldError> {
-        if x < 0 || y < 0 {
-            return Err(WorldError::OutOfBounds);
-        }
-        
-        let x = x as usize;
-        let y = y as usize;
-
-        if x >= self.width || y >= self.height {
-            return Err(WorldError::OutOfBounds);
-        }
-
-        let i: usize = y * self.width + x;
-
-        self.map[i] = s;
-
-        Ok(())
+    fn set(&mut self, pos: Point, s: Space) -> Result<(), WorldError> {
+        if self.map.set(pos, s) { Ok(()) } else { Err(WorldError::OutOfBounds) }
     }
 }
 
@@ -87,45 +49,11 @@ enum Space {
 }
 
 
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl TryFrom<char> for Direction {
-    type Error = String;
-
-    fn try_from(s: char) -> Result<Self, Self::Error> {
-        match s {
-            '^' => Ok(Direction::Up),
-            'v' => Ok(Direction::Down),
-            '<' => Ok(Direction::Left),
-            '>' => Ok(Direction::Right),
-            _ => Err(format!("Invalid direction: {}", s)),
-        }
-    }
-}
-
-impl Direction {
-    fn turn_right(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Right,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            Direction::Right => Direction::Down,
-        }
-    }
-}
-
 #[derive(Clone, Debug)]
 struct Guard {
-    x: i64,
-    y: i64,
+    pos: Point,
     dir: Direction,
-    path: HashSet<(i64, i64, Direction)>,
+    path: HashSet<(Point, Direction)>,
 }
 
 #[derive(Debug)]
@@ -135,32 +63,23 @@ enum WorldError {
 }
 
 impl Guard {
-    fn new(x: usize, y: usize, dir: Direction) -> Self {
-        let x = x.try_into().unwrap();
-        let y = y.try_into().unwrap();
-
-        Self { x, y, dir: dir.clone(), path: HashSet::from([(x,y,dir)]) }
+    fn new(pos: Point, dir: Direction) -> Self {
+        Self { pos, dir, path: HashSet::from([(pos, dir)]) }
     }
 
-    fn next_pos(&self) -> (i64, i64) {
-        match self.dir {
-            Direction::Up => (self.x, self.y - 1),
-            Direction::Down => (self.x, self.y + 1),
-            Direction::Left => (self.x - 1, self.y),
-            Direction::Right => (self.x + 1, self.y),
-        }
+    fn next_pos(&self) -> Point {
+        self.pos + self.dir.vector()
     }
 
     fn move_forward(&mut self) -> Result<(), WorldError> {
-        let (x, y) = self.next_pos();
-        let step = (x, y, self.dir.clone());
+        let pos = self.next_pos();
+        let step = (pos, self.dir);
         
         if self.path.contains(&step) {
             return Err(WorldError::LoopDetected);
         }
 
-        self.x = x;
-        self.y = y;
+        self.pos = pos;
 
         self.path.insert(step);
 
@@ -172,71 +91,71 @@ impl Guard {
     }
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let mut w = World::from_str(input).or_else(|e| {
-        eprintln!("{}", e);
-        Err(0)
-    }).unwrap();
-
-    while let Ok(s) = w.get(w.guard.next_pos()) {
-        match s {
-            Space::Empty => {
-                if w.guard.move_forward().is_err() {
-                    panic!("Loop detected");
-                }
-            },
-            Space::Obstacle => {
-                w.guard.turn_right();
-            },
-        }
+impl Solution for Solver {
+    type Input = World;
+
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input.parse()
     }
 
-    let unique_coords: HashSet<(i64, i64)> = w.guard.path.into_iter().map(|step| (step.0, step.1)).collect();
-    
-    Box::new(unique_coords.len())
-}
+    fn part1(world: &Self::Input) -> Answer {
+        let mut w = world.clone();
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let mut w = World::from_str(input).or_else(|e| {
-        eprintln!("{}", e);
-        Err(0)
-    }).unwrap();
-    
-    let original_guard = w.guard.clone();
-
-    while let Ok(s) = w.get(w.guard.next_pos()) {
-        match s {
-            Space::Empty => {
-                if w.guard.move_forward().is_err() {
-                    panic!("Loop detected");
-                }
-            },
-            Space::Obstacle => {
-                w.guard.turn_right();
-            },
+        while let Ok(s) = w.get(w.guard.next_pos()) {
+            match s {
+                Space::Empty => {
+                    if w.guard.move_forward().is_err() {
+                        panic!("Loop detected");
+                    }
+                },
+                Space::Obstacle => {
+                    w.guard.turn_right();
+                },
+            }
         }
+
+        let unique_coords: HashSet<Point> = w.guard.path.into_iter().map(|(pos, _)| pos).collect();
+
+        unique_coords.len().into()
     }
 
-    let unique_coords: HashSet<(i64, i64)> = w.guard.path.iter().map(|step| (step.0, step.1)).collect();
-    
-    let loop_count = Arc::new(Mutex::new(0));
+    fn part2(world: &Self::Input) -> Answer {
+        let mut w = world.clone();
 
-    let mut handles = vec![];
+        let original_guard = w.guard.clone();
 
-    for pos in unique_coords {
-        if original_guard.x == pos.0 && original_guard.y == pos.1 {
-            continue;
+        while let Ok(s) = w.get(w.guard.next_pos()) {
+            match s {
+                Space::Empty => {
+                    if w.guard.move_forward().is_err() {
+                        panic!("Loop detected");
+                    }
+                },
+                Space::Obstacle => {
+                    w.guard.turn_right();
+                },
+            }
         }
 
-        let mut world = w.clone();
-        world.guard = original_guard.clone();
-        let loop_count = Arc::clone(&loop_count);
+        let unique_coords: HashSet<Point> = w.guard.path.iter().map(|&(pos, _)| pos).collect();
+
+        let loop_count = Arc::new(Mutex::new(0));
+
+        let mut handles = vec![];
+
+        for pos in unique_coords {
+            if original_guard.pos == pos {
+                continue;
+            }
+
+            let mut world = w.clone();
+            world.guard = original_guard.clone();
+            let loop_count = Arc::clone(&loop_count);
 
-        let handle = spawn(move || {
-            world.set(pos, Space::Obstacle).unwrap();
+            let handle = spawn(move || {
+                world.set(pos, Space::Obstacle).unwrap();
 
-            loop {
-                if let Ok(s) = world.get(world.guard.next_pos()) {
+                while let Ok(s) = world.get(world.guard.next_pos()) {
                     match s {
                         Space::Empty => {
                             if world.guard.move_forward().is_err() {
@@ -249,30 +168,24 @@ pub fn part2(input: &String) -> Box<dyn ToString> {
                             world.guard.turn_right();
                         }
                     }
-                } else {
-                    break;
                 }
-            }
-        });
+            });
 
-        handles.push(handle);
-    }
+            handles.push(handle);
+        }
 
-    for handle in handles {
-        handle.join().unwrap();
-    }
+        for handle in handles {
+            handle.join().unwrap();
+        }
 
-    let loop_count = Arc::try_unwrap(loop_count).unwrap().into_inner().unwrap();
+        let loop_count = Arc::try_unwrap(loop_count).unwrap().into_inner().unwrap();
 
-    Box::new(loop_count)
+        loop_count.into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-    
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         ....#.....
         .........#
         ..........
@@ -283,17 +196,24 @@ mod tests {
         ........#.
         #.........
         ......#..."
-    };
+    }).part1("41").part2("6"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 41;
     const TEST_RESULT2: i64 = 6;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
 }
\ No newline at end of file
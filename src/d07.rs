@@ -1,6 +1,14 @@
+use indoc::indoc;
 use rayon::prelude::*;
 use std::sync::atomic::{AtomicI64, Ordering};
 
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+/// The expected result of an equation and its operands.
+type Equation = (usize, Vec<usize>);
+
 #[derive(Clone, Copy)]
 enum Op {
     Add,
@@ -8,97 +16,110 @@ enum Op {
     Concat,
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let mut sum = 0;
+impl Solution for Solver {
+    type Input = Vec<Equation>;
+
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input.lines().map(|line| {
+            let (left, right) = line
+                .split_once(":")
+                .ok_or_else(|| ParseError::new("Missing ':'").at(input, line).expected("an equation like 190: 10 19"))?;
+            let result = left
+                .parse()
+                .map_err(|_| ParseError::new(format!("{} is not a number", left)).at(input, left))?;
+            let nums = right
+                .split_whitespace()
+                .map(|n| n.parse().map_err(|_| ParseError::new(format!("{} is not a number", n)).at(input, n)))
+                .collect::<Result<Vec<usize>, _>>()?;
+            if nums.is_empty() {
+                return Err(ParseError::new("The equation has no operands").at(input, line).expected("an equation like 190: 10 19"));
+            }
+
+            Ok((result, nums))
+        }).collect()
+    }
+
+    fn part1(equations: &Self::Input) -> Answer {
+        let mut sum = 0;
 
-    for line in input.lines() {
-        let (left, right) = line.split_once(":").unwrap();
-        let result: usize = left.parse().unwrap();
-        let nums: Vec<usize> = right.split_whitespace().map(|n| n.parse().unwrap()).collect();
-        let num_permutations = 1 << (nums.len() - 1);
+        for (result, nums) in equations {
+            let result = *result;
+            let num_permutations = 1 << (nums.len() - 1);
 
-        'nextOp: for op in 0..num_permutations {
-            let mut tmp = nums[0];
+            'nextOp: for op in 0..num_permutations {
+                let mut tmp = nums[0];
 
-            for i in 1..nums.len() {
-                if op & (1 << (i - 1)) != 0 {
-                    tmp += nums[i];
-                } else {
-                    tmp *= nums[i];
-                }
+                for (i, &num) in nums.iter().enumerate().skip(1) {
+                    if op & (1 << (i - 1)) != 0 {
+                        tmp += num;
+                    } else {
+                        tmp *= num;
+                    }
 
-                if tmp > result {
-                    continue 'nextOp;
+                    if tmp > result {
+                        continue 'nextOp;
+                    }
                 }
-            }
 
-            if tmp == result {
-                sum += result;
-                break;
+                if tmp == result {
+                    sum += result;
+                    break;
+                }
             }
         }
+
+        sum.into()
     }
 
-    Box::new(sum)
-}
+    fn part2(equations: &Self::Input) -> Answer {
+        let sum = AtomicI64::new(0);
+
+        equations.par_iter().for_each(|(result, nums)| {
+            let result = *result;
+            let num_ops = nums.len() - 1;
+            let total_permutations = 3usize.pow(num_ops as u32);
+
+            for perm in 0..total_permutations {
+                let mut tmp = nums[0];
+                let mut current_perm = perm;
+                let mut valid = true;
+
+                for &num in &nums[1..] {
+                    let op = match current_perm % 3 {
+                        0 => Op::Add,
+                        1 => Op::Mul,
+                        2 => Op::Concat,
+                        _ => unreachable!(),
+                    };
+                    current_perm /= 3;
+
+                    match op {
+                        Op::Add => tmp += num,
+                        Op::Mul => tmp *= num,
+                        Op::Concat => {
+                            tmp = format!("{}{}", tmp, num).parse::<usize>().unwrap_or(0);
+                        }
+                    }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let sum = AtomicI64::new(0);
-
-    input.par_lines().for_each(|line| {
-        let (left, right) = line.split_once(":").unwrap();
-        let result = left.parse::<usize>().unwrap();
-        let nums: Vec<usize> = right
-            .split_whitespace()
-            .map(|n| n.parse().unwrap())
-            .collect();
-        let num_ops = nums.len() - 1;
-        let total_permutations = 3usize.pow(num_ops as u32);
-
-        for perm in 0..total_permutations {
-            let mut tmp = nums[0];
-            let mut current_perm = perm;
-            let mut valid = true;
-
-            for i in 1..nums.len() {
-                let op = match current_perm % 3 {
-                    0 => Op::Add,
-                    1 => Op::Mul,
-                    2 => Op::Concat,
-                    _ => unreachable!(),
-                };
-                current_perm /= 3;
-
-                match op {
-                    Op::Add => tmp += nums[i],
-                    Op::Mul => tmp *= nums[i],
-                    Op::Concat => {
-                        tmp = format!("{}{}", tmp, nums[i]).parse::<usize>().unwrap_or(0);
+                    if tmp > result {
+                        valid = false;
+                        break;
                     }
                 }
 
-                if tmp > result {
-                    valid = false;
+                if valid && tmp == result {
+                    sum.fetch_add(result as i64, Ordering::SeqCst);
                     break;
                 }
             }
+        });
 
-            if valid && tmp == result {
-                sum.fetch_add(result as i64, Ordering::SeqCst);
-                break;
-            }
-        }
-    });
-
-    Box::new(sum.load(Ordering::SeqCst))
+        sum.load(Ordering::SeqCst).into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         190: 10 19
         3267: 81 40 27
         83: 17 5
@@ -108,17 +129,29 @@ mod tests {
         192: 17 8 14
         21037: 9 7 18 13
         292: 11 6 16 20"
-    };
+    }).part1("3749").part2("11387"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 3749;
     const TEST_RESULT2: i64 = 11387;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(Solver::parse("190: 10 19\n3267:").unwrap_err().to_string().contains("no operands"));
     }
 }
\ No newline at end of file
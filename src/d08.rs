@@ -1,170 +1,107 @@
 use std::collections::{HashMap, HashSet};
 
-struct Pos {
-    kind: Space,
-    coord: Coord,
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Coord {
-    x: i64,
-    y: i64,
-}
-
-impl Coord {
-    fn new(x: i64, y: i64) -> Coord {
-        Coord { x, y }
-    }
-}
+use indoc::indoc;
 
-impl std::ops::Sub for Coord {
-    type Output = Coord;
+use crate::{error::ParseError, examples::Example, geometry::Point, grid::Grid, params::Params, solution::{Answer, Solution}};
 
-    fn sub(self, other: Coord) -> Coord {
-        Coord::new(self.x - other.x, self.y - other.y )
-    }
-}
-
-impl std::ops::SubAssign for Coord {
-    fn sub_assign(&mut self, other: Coord) {
-        self.x -= other.x;
-        self.y -= other.y;
-    }
-}
-
-impl std::ops::Add for Coord {
-    type Output = Coord;
-
-    fn add(self, other: Coord) -> Coord {
-        Coord::new(self.x + other.x, self.y + other.y )
-    }
-}
+pub struct Solver;
 
-impl std::ops::AddAssign for Coord {
-    fn add_assign(&mut self, other: Coord) {
-        self.x += other.x;
-        self.y += other.y;
-    }
-}
-
-enum Space {
+pub enum Space {
     Empty,
     Antenna(char),
 }
 
-fn create_map(input: &String) -> (Vec<Pos>, i64, i64) {
-    let mut map = Vec::new();
-
-    let mut w = 0;
-    let mut h = 0;
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let kind = match c {
-                '.' => Space::Empty,
-                _ => Space::Antenna(c),
-            };
+fn index_map(map: &Grid<Space>) -> HashMap<char, Vec<Point>> {
+    let mut index = HashMap::new();
 
-            map.push(Pos {
-                kind,
-                coord: Coord::new(x as i64, y as i64),
-            });
+    for (pos, space) in map.iter() {
+        if let Space::Antenna(c) = space {
+            index.entry(*c).or_insert_with(Vec::new).push(pos);
         }
-
-        w = line.len() as i64;
-        h = y as i64;
     }
 
-    (map, w, h+1)
+    index
 }
 
-fn index_map(map: Vec<Pos>) -> HashMap<char, Vec<Coord>> {
-    let mut index = HashMap::new();
 
-    for pos in map {
-        if let Space::Antenna(c) = pos.kind {
-            index.entry(c).or_insert(Vec::new()).push(pos.coord);
-        }
-    }
+impl Solution for Solver {
+    type Input = (HashMap<char, Vec<Point>>, Grid<Space>);
 
-    index
-}
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        let map = Grid::parse(input, |c| match c {
+            '.' => Ok(Space::Empty),
+            _ => Ok(Space::Antenna(c)),
+        })?;
 
+        Ok((index_map(&map), map))
+    }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let (map, w, h) = create_map(input);
-    let index = index_map(map);
-    let mut nodes = HashSet::new();
+    fn part1((index, map): &Self::Input) -> Answer {
+        let mut nodes = HashSet::new();
 
-    for pos in index.into_values() {
-        for i in 0..pos.len() {
-            for j in i+1..pos.len() {
-                let d = pos[j] - pos[i];
-                let a = pos[i] - d;
-                let b = pos[j] + d;
+        for pos in index.values() {
+            for i in 0..pos.len() {
+                for j in i+1..pos.len() {
+                    let d = pos[j] - pos[i];
+                    let a = pos[i] - d;
+                    let b = pos[j] + d;
 
-                if a.x >= 0 && a.x < w && a.y >= 0 && a.y < h {
-                    nodes.insert(a);
-                }
-                if b.x >= 0 && b.x < w && b.y >= 0 && b.y < h {
-                    nodes.insert(b);
+                    if map.in_bounds(a) {
+                        nodes.insert(a);
+                    }
+                    if map.in_bounds(b) {
+                        nodes.insert(b);
+                    }
                 }
             }
         }
+
+        nodes.len().into()
     }
 
-    Box::new(nodes.len())
-}
+    fn part2((index, map): &Self::Input) -> Answer {
+        let mut nodes = HashSet::new();
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let (map, w, h) = create_map(input);
-    let index = index_map(map);
-    let mut nodes = HashSet::new();
-
-    for pos in index.into_values() {
-        for i in 0..pos.len() {
-            for j in i+1..pos.len() {
-                let mut a = pos[i];
-                let mut b = pos[j];
-
-                // we need to go backwards here, 
-                // so that we also "cover" the antennas themselves with antinodes
-                let d = a - b;
-
-                loop {
-                    a -= d;
-    
-                    if a.x >= 0 && a.x < w && a.y >= 0 && a.y < h {
-                        nodes.insert(a);
-                    } else {
-                        break;
+        for pos in index.values() {
+            for i in 0..pos.len() {
+                for j in i+1..pos.len() {
+                    let mut a = pos[i];
+                    let mut b = pos[j];
+
+                    // we need to go backwards here, 
+                    // so that we also "cover" the antennas themselves with antinodes
+                    let d = a - b;
+
+                    loop {
+                        a -= d;
+
+                        if map.in_bounds(a) {
+                            nodes.insert(a);
+                        } else {
+                            break;
+                        }
                     }
-                }
-                
-                loop {
-                    b += d;
 
-                    if b.x >= 0 && b.x < w && b.y >= 0 && b.y < h {
-                        nodes.insert(b);
-                    } else {
-                        break;
+                    loop {
+                        b += d;
+
+                        if map.in_bounds(b) {
+                            nodes.insert(b);
+                        } else {
+                            break;
+                        }
                     }
-                }
 
+                }
             }
         }
-    }
-
-    Box::new(nodes.len())
 
+        nodes.len().into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         ............
         ........0...
         .....0......
@@ -177,17 +114,24 @@ mod tests {
         .........A..
         ............
         ............"
-    };
+    }).part1("14").part2("34"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 14;
     const TEST_RESULT2: i64 = 34;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
 }
\ No newline at end of file
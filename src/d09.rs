@@ -1,3 +1,7 @@
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Block {
@@ -17,12 +21,12 @@ struct Chunk {
     size: i64,
 }
 
-fn parse(input: &String) -> Vec<Block> {
+fn blocks(sizes: &[i64]) -> Vec<Block> {
     let mut id = 0;
     let mut disk: Vec<Block> = Vec::new();
     
     let mut is_file = true;
-    for c in input.chars().map(|c| c.to_string().parse::<i64>().unwrap()) {
+    for &c in sizes {
 
         if c > 0 {
             if is_file {
@@ -43,9 +47,9 @@ fn parse(input: &String) -> Vec<Block> {
     disk
 }
 
-fn checksum(disk: &Vec<Block>) -> i64 {
+fn checksum(disk: &[Block]) -> i64 {
     let mut checksum = 0;
-    for (i, b) in disk.into_iter().enumerate() {
+    for (i, b) in disk.iter().enumerate() {
         if let Block::File(id) = b {
             checksum += id * (i as i64);
         }
@@ -53,46 +57,12 @@ fn checksum(disk: &Vec<Block>) -> i64 {
     checksum
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let disk = parse(input);
-    let mut fragmented = Vec::new();
-
-    let filesize = disk.iter().filter(|&x| match x {
-        Block::File(_) => true,
-        _ => false,
-    }).count();
-
-    let disk_clone = disk.clone();
-    let mut reverse_files = disk_clone.into_iter().rev().filter(|&x| match x {
-        Block::File(_) => true,
-        _ => false,
-    });
-    
-    for b in disk.into_iter() {
-        if b == Block::Space {
-            if let Some(f) = reverse_files.next() {
-                fragmented.push(f);
-            } else {
-                break;
-            }
-        } else {
-            if fragmented.len() < filesize {
-                fragmented.push(b);
-            } else {
-                break;
-            }
-        }
-    }
-
-    Box::new(checksum(&fragmented))
-}
-
-fn parse2(input: &String) -> Vec<Chunk> {
+fn chunks(sizes: &[i64]) -> Vec<Chunk> {
     let mut id = 0;
     let mut disk: Vec<Chunk> = Vec::new();
     
     let mut is_file = true;
-    for c in input.chars().map(|c| c.to_string().parse::<i64>().unwrap()) {
+    for &c in sizes {
 
         if c > 0 {
             if is_file {
@@ -110,13 +80,51 @@ fn parse2(input: &String) -> Vec<Chunk> {
 
 }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let mut disk = parse2(input);
+impl Solution for Solver {
+    type Input = Vec<i64>;
 
-    for chunk in disk.clone().into_iter().rev() {
-    
-        match chunk.kind {
-            ChunkKind::File(id) => {
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        let input = input.trim_end();
+
+        input
+            .char_indices()
+            .map(|(i, c)| c.to_digit(10).map(i64::from).ok_or_else(|| {
+                ParseError::new(format!("{} is not a digit", c)).at(input, &input[i..i + c.len_utf8()])
+            }))
+            .collect()
+    }
+
+    fn part1(sizes: &Self::Input) -> Answer {
+        let disk = blocks(sizes);
+        let mut fragmented = Vec::new();
+
+        let filesize = disk.iter().filter(|&x| matches!(x, Block::File(_))).count();
+
+        let disk_clone = disk.clone();
+        let mut reverse_files = disk_clone.into_iter().rev().filter(|&x| matches!(x, Block::File(_)));
+
+        for b in disk.into_iter() {
+            if b == Block::Space {
+                if let Some(f) = reverse_files.next() {
+                    fragmented.push(f);
+                } else {
+                    break;
+                }
+            } else if fragmented.len() < filesize {
+                fragmented.push(b);
+            } else {
+                break;
+            }
+        }
+
+        checksum(&fragmented).into()
+    }
+
+    fn part2(sizes: &Self::Input) -> Answer {
+        let mut disk = chunks(sizes);
+
+        for chunk in disk.clone().into_iter().rev() {
+            if let ChunkKind::File(id) = chunk.kind {
                 if let Some(space_i) = disk.iter().position(|c| match c.kind {
                     ChunkKind::Space => c.size >= chunk.size,
                     _ => false,
@@ -145,39 +153,41 @@ pub fn part2(input: &String) -> Box<dyn ToString> {
                         disk.insert(space_i + 1, Chunk {kind: ChunkKind::Space, size: space - chunk.size});
                     }
                 }
-            },
-            _ => (),
-        };
-    }
+            }
+        }
 
 
-    let mut compact = Vec::new();
-    for chunk in disk.into_iter() {
-        match chunk.kind {
-            ChunkKind::File(id) => for _ in 0..chunk.size { compact.push(Block::File(id)) },
-            ChunkKind::Space => for _ in 0..chunk.size { compact.push(Block::Space) },
+        let mut compact = Vec::new();
+        for chunk in disk.into_iter() {
+            match chunk.kind {
+                ChunkKind::File(id) => for _ in 0..chunk.size { compact.push(Block::File(id)) },
+                ChunkKind::Space => for _ in 0..chunk.size { compact.push(Block::Space) },
+            }
         }
-    }
-
-    Box::new(checksum(&compact))
 
+        checksum(&compact).into()
+    }
 }
 
+pub const EXAMPLES: &[Example] = &[
+    Example::new("2333133121414131402").part1("1928").part2("2858"),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "2333133121414131402";
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 1928;
     const TEST_RESULT2: i64 = 2858;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
 }
\ No newline at end of file
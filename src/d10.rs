@@ -1,182 +1,110 @@
-use std::{
-    collections::HashMap,
-    sync::atomic::{AtomicUsize, Ordering},
-};
+use std::collections::HashMap;
 
+use indoc::indoc;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-type Map = Vec<Vec<u8>>;
-type Coord = (usize, usize);
+use crate::{error::ParseError, examples::Example, geometry::Point, grid::Grid, params::Params, search, solution::{Answer, Solution}};
 
-// Create a map from the input string
-fn create_map(input: &String) -> Map {
-    let mut map = Vec::new();
+pub struct Solver;
 
-    for line in input.lines() {
-        // Convert each character to a u8 and subtract '0' to get the numeric value
-        map.push(line.chars().map(|c| (c as u8) - ('0' as u8)).collect());
-    }
+type Map = Grid<u8>;
+type Coord = Point;
+
+// Create a map from the input string
+fn create_map(input: &str) -> Result<Map, ParseError> {
+    Grid::parse(input, |c| {
+        c.to_digit(10).map(|d| d as u8).ok_or_else(|| ParseError::new(format!("Invalid height: {}", c)).expected("a digit"))
+    })
+}
 
-    map
+// Find all coordinates with the given height
+fn with_height(map: &Map, height: u8) -> Vec<Coord> {
+    map.iter().filter(|&(_, &h)| h == height).map(|(coord, _)| coord).collect()
 }
 
 // Find all coordinates where the map value is 9 (peaks)
 fn peaks(map: &Map) -> Vec<Coord> {
-    let mut peaks = Vec::new();
-
-    for y in 0..map.len() {
-        for x in 0..map[y].len() {
-            if map[y][x] == 9 {
-                peaks.push((x, y));
-            }
-        }
-    }
-
-    peaks
+    with_height(map, 9)
 }
 
 // Find all coordinates where the map value is 0 (starting points)
 fn starts(map: &Map) -> Vec<Coord> {
-    let mut starts = Vec::new();
-
-    for y in 0..map.len() {
-        for x in 0..map[y].len() {
-            if map[y][x] == 0 {
-                starts.push((x, y));
-            }
-        }
-    }
-
-    starts
+    with_height(map, 0)
 }
 
 // Get the neighboring coordinates with the next consecutive value
-fn next(map: &Map, coord: &Coord) -> Result<Vec<Coord>, ()> {
-    let this_num = map[coord.1][coord.0];
+fn next(map: &Map, &coord: &Coord) -> Result<Vec<Coord>, ()> {
+    let this_num = map.get(coord).copied().ok_or(())?;
     if this_num == 9 {
         // Cannot proceed further if current number is 9
         return Err(());
     }
     let next_num = this_num + 1;
 
-    let mut next = Vec::new();
-    // Check the left neighbor
-    if coord.0 > 0 {
-        if map[coord.1][coord.0 - 1] == next_num {
-            next.push((coord.0 - 1, coord.1));
-        }
-    }
-    // Check the right neighbor
-    if coord.0 < map[coord.1].len() - 1 {
-        if map[coord.1][coord.0 + 1] == next_num {
-            next.push((coord.0 + 1, coord.1));
-        }
-    }
-    // Check the top neighbor
-    if coord.1 > 0 {
-        if map[coord.1 - 1][coord.0] == next_num {
-            next.push((coord.0, coord.1 - 1));
-        }
-    }
-    // Check the bottom neighbor
-    if coord.1 < map.len() - 1 {
-        if map[coord.1 + 1][coord.0] == next_num {
-            next.push((coord.0, coord.1 + 1));
-        }
-    }
-
-    Ok(next)
+    Ok(map.neighbours4(coord).filter(|&n| map.get(n) == Some(&next_num)).collect())
 }
 
-// Part 1: Count the number of peaks reachable from starting points
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let map = create_map(input);
-    let peaks = peaks(&map);
-    let starts = starts(&map);
-
-    // Recursive function to determine if a path from a coordinate can reach a peak
-    fn find(map: &Map, coord: &Coord, cache: &mut HashMap<Coord, bool>) -> bool {
-        if let Some(&found) = cache.get(coord) {
-            // Return cached result if available
-            return found;
-        }
-
-        let mut found = false;
-        if let Ok(next_coords) = next(map, coord) {
-            for next_coord in next_coords {
-                if find(map, &next_coord, cache) {
-                    found = true;
-                }
-            }
-        } else {
-            // Reached a peak
-            found = true;
-        }
+impl Solution for Solver {
+    type Input = Map;
 
-        cache.insert(*coord, found);
-        found
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        create_map(input)
     }
 
-    let count = AtomicUsize::new(0);
+    // Part 1: Count the number of peaks reachable from starting points
+    fn part1(map: &Self::Input) -> Answer {
+        let peaks = peaks(map);
+        let starts = starts(map);
 
-    // Parallel iteration over all starting points
-    starts.par_iter().for_each(|start| {
-        let mut cache: HashMap<Coord, bool> = HashMap::new();
-        find(&map, start, &mut cache);
+        // Parallel iteration over all starting points, counting the peaks among the reachable coordinates
+        let count: usize = starts.par_iter().map(|&start| {
+            let reachable = search::bfs_distances(start, |coord| next(map, coord).unwrap_or_default());
 
-        // Count the number of peaks reachable from this starting point
-        count.fetch_add(
-            peaks.iter().filter(|&&peak| cache.get(&peak).copied().unwrap_or(false)).count(),
-            Ordering::SeqCst,
-        );
-    });
+            peaks.iter().filter(|peak| reachable.contains_key(peak)).count()
+        }).sum();
 
-    Box::new(count.load(Ordering::SeqCst))
-}
+        count.into()
+    }
 
-// Part 2: Count the total number of paths from starting points to peaks
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let map = create_map(input);
-    let starts = starts(&map);
+    // Part 2: Count the total number of paths from starting points to peaks
+    fn part2(map: &Self::Input) -> Answer {
+        let starts = starts(map);
 
-    // Recursive function to count the number of paths from a coordinate to peaks
-    fn find(map: &Map, coord: &Coord, cache: &mut HashMap<Coord, i64>) -> i64 {
-        if let Some(&count) = cache.get(coord) {
-            // Return cached count if available
-            return count;
-        }
+        // Recursive function to count the number of paths from a coordinate to peaks
+        fn find(map: &Map, coord: &Coord, cache: &mut HashMap<Coord, i64>) -> i64 {
+            if let Some(&count) = cache.get(coord) {
+                // Return cached count if available
+                return count;
+            }
 
-        let mut total_paths = 0;
-        if let Ok(next_coords) = next(map, coord) {
-            for next_coord in next_coords {
-                total_paths += find(map, &next_coord, cache);
+            let mut total_paths = 0;
+            if let Ok(next_coords) = next(map, coord) {
+                for next_coord in next_coords {
+                    total_paths += find(map, &next_coord, cache);
+                }
+            } else {
+                // Reached a peak
+                total_paths = 1;
             }
-        } else {
-            // Reached a peak
-            total_paths = 1;
-        }
 
-        cache.insert(*coord, total_paths);
-        total_paths
-    }
+            cache.insert(*coord, total_paths);
+            total_paths
+        }
 
-    let mut count = 0;
+        let mut count = 0;
 
-    // Iterate over all starting points
-    starts.iter().for_each(|start| {
-        let mut cache: HashMap<Coord, i64> = HashMap::new();
-        count += find(&map, start, &mut cache);
-    });
+        // Iterate over all starting points
+        starts.iter().for_each(|start| {
+            let mut cache: HashMap<Coord, i64> = HashMap::new();
+            count += find(map, start, &mut cache);
+        });
 
-    Box::new(count)
+        count.into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         89010123
         78121874
         87430965
@@ -185,19 +113,26 @@ mod tests {
         32019012
         01329801
         10456732"
-    };
+    }).part1("36").part2("81"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT1: i64 = 36;
     const TEST_RESULT2: i64 = 81;
 
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     // Test for part2
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
 }
\ No newline at end of file
@@ -1,7 +1,18 @@
 use std::collections::HashMap;
-type LookupCache = Vec<HashMap<i64,i64>>;
 
-fn do_rules(stone: i64, depth: usize, cache: &mut LookupCache) -> i64 {
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+type LookupCache = Vec<HashMap<u64,u64>>;
+
+/// The engraved stones with how many times each part blinks.
+pub struct Stones {
+    stones: Vec<u64>,
+    blinks: [usize; 2],
+}
+
+fn do_rules(stone: u64, depth: usize, cache: &mut LookupCache) -> u64 {
     if let Some(result) = cache[depth].get(&stone) {
         return *result;
     }
@@ -12,10 +23,10 @@ fn do_rules(stone: i64, depth: usize, cache: &mut LookupCache) -> i64 {
         do_rules(1, depth - 1, cache)
     } else {
         let digits = stone.ilog10() + 1;
-        if digits % 2 == 0 {
+        if digits.is_multiple_of(2) {
             let half = digits / 2;
-            let left = stone / 10_i64.pow(half as u32);
-            let right = stone % 10_i64.pow(half as u32);
+            let left = stone / 10_u64.pow(half);
+            let right = stone % 10_u64.pow(half);
 
             do_rules(left, depth - 1, cache) + do_rules(right, depth - 1, cache)
         } else {
@@ -29,34 +40,57 @@ fn do_rules(stone: i64, depth: usize, cache: &mut LookupCache) -> i64 {
     result
 }
 
-fn parse_and_do(input: &String, depth: usize) -> i64 {
+fn blink(stones: &[u64], depth: usize) -> u64 {
 
-    let mut cache: LookupCache = vec![HashMap::new(); depth as usize + 1];
+    let mut cache: LookupCache = vec![HashMap::new(); depth + 1];
 
-    input
-        .split_whitespace()
-        .map(|x| x.parse().unwrap())
-        .map(|x| do_rules(x, depth, &mut cache))
+    stones
+        .iter()
+        .map(|&x| do_rules(x, depth, &mut cache))
         .sum()
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    Box::new(parse_and_do(input, 25))
-}
+impl Solution for Solver {
+    type Input = Stones;
+
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("part1_blinks", "25"), ("part2_blinks", "75")];
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    Box::new(parse_and_do(input, 75))
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        let stones = input
+            .split_whitespace()
+            .map(|x| x.parse().map_err(|_| ParseError::new(format!("{} is not a number", x)).at(input, x)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Stones { stones, blinks: [params.get("part1_blinks")?, params.get("part2_blinks")?] })
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        blink(&input.stones, input.blinks[0]).into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        blink(&input.stones, input.blinks[1]).into()
+    }
 }
 
+pub const EXAMPLES: &[Example] = &[
+    Example::new("125 17").part1("55312"),
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    const TEST_INPUT: &str = "125 17";
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT: i64 = 55312;
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT.into());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(Solver::parse("125 -17").err().unwrap().to_string().contains("-17 is not a number"));
     }
 }
\ No newline at end of file
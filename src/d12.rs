@@ -1,102 +1,65 @@
 use std::collections::HashSet;
 
+use indoc::indoc;
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
-type Grid = Vec<Vec<char>>;
-type Area = HashSet<(usize, usize)>;
+use crate::{error::ParseError, examples::Example, geometry::{Dir8, Direction, Point}, grid::Grid, params::Params, solution::{Answer, Solution}};
 
-pub fn make_grid(input: &String) -> Grid {
-    input
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
-}
+pub struct Solver;
+
+type Area = HashSet<Point>;
 
 // copied this from wikipedia (https://en.wikipedia.org/wiki/Flood_fill stack based not recursive)
-fn flood_fill(grid: &Grid, x: usize, y: usize, visited: &mut Vec<Vec<bool>>) -> Area {
+fn flood_fill(grid: &Grid<char>, start: Point, visited: &mut Grid<bool>) -> Area {
     let mut area = Area::new();
-    let mut stack = vec![(x, y)];
+    let mut stack = vec![start];
 
-    while let Some((x, y)) = stack.pop() {
-        if visited[y][x] {
+    while let Some(p) = stack.pop() {
+        if visited.get(p) == Some(&true) {
             continue;
         }
-        visited[y][x] = true;
+        visited.set(p, true);
 
-        area.insert((x, y));
+        area.insert(p);
 
-        if x > 0 && grid[y][x - 1] == grid[y][x] {
-            stack.push((x - 1, y));
-        }
-        if x < grid[y].len() - 1 && grid[y][x + 1] == grid[y][x] {
-            stack.push((x + 1, y));
-        }
-        if y > 0 && grid[y - 1][x] == grid[y][x] {
-            stack.push((x, y - 1));
-        }
-        if y < grid.len() - 1 && grid[y + 1][x] == grid[y][x] {
-            stack.push((x, y + 1));
-        }
+        let plant = grid.get(p);
+        stack.extend(grid.neighbours4(p).filter(|&n| grid.get(n) == plant));
     }
 
     area
 }
 
-fn perimeter(area: &Area) -> i64 {
-    let mut perimeter = 0;
-    for (x, y) in area {
-        if !area.contains(&(x + 1, *y)) {
-            perimeter += 1;
-        }
-        if *x == 0 || !area.contains(&(x - 1, *y)) {
-            perimeter += 1;
-        }
-        if !area.contains(&(*x, y + 1)) {
-            perimeter += 1;
-        }
-        if *y == 0 || !area.contains(&(*x, y - 1)) {
-            perimeter += 1;
-        }
-    }
-    perimeter
-}
-
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let grid = make_grid(input);
+fn areas(grid: &Grid<char>) -> Vec<Area> {
     let mut areas: Vec<Area> = vec![];
 
-    let mut visited = vec![vec![false; grid[0].len()]; grid.len()];
-    
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            if visited[y][x] {
-                continue;
-            }
-            let new_area = flood_fill(&grid, x, y, &mut visited);
-            areas.push(new_area);
+    let mut visited = Grid::new(grid.width(), grid.height(), false);
+
+    for p in grid.positions() {
+        if visited.get(p) == Some(&true) {
+            continue;
         }
+        let new_area = flood_fill(grid, p, &mut visited);
+        areas.push(new_area);
     }
 
-    Box::new(areas.into_par_iter().map(|a| {
-        perimeter(&a) * a.len() as i64
-    }).sum::<i64>())
+    areas
 }
 
+fn perimeter(area: &Area) -> i64 {
+    let mut perimeter = 0;
+    for &p in area {
+        perimeter += Direction::ALL.iter().filter(|d| !area.contains(&(p + d.vector()))).count() as i64;
+    }
+    perimeter
+}
 
 fn sides(area: &Area) -> i64 {
     // we actually need to count corners
 
     let mut corners = 0;
-    for (x, y) in area {
+    for &p in area {
         // we use those multiple times, so we hash them only once here
-        let tl = *x > 0 && *y > 0 && area.contains(&(x - 1, y - 1));
-        let t = *y > 0 && area.contains(&(*x, y - 1));
-        let tr = *y > 0 && area.contains(&(x + 1, y - 1));
-        let r = area.contains(&(x + 1, *y));
-        let br = area.contains(&(x + 1, y + 1));
-        let b = area.contains(&(*x, y + 1));
-        let bl = *x > 0 && area.contains(&(x - 1, y + 1));
-        let l = *x > 0 && area.contains(&(x - 1, *y));
+        let [t, tr, r, br, b, bl, l, tl] = Dir8::ALL.map(|d| area.contains(&(p + d.vector())));
 
         // Convex corners
         // bottom right
@@ -137,54 +100,41 @@ fn sides(area: &Area) -> i64 {
     corners
 }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let grid = make_grid(input);
-    let mut areas: Vec<Area> = vec![];
+impl Solution for Solver {
+    type Input = Grid<char>;
 
-    let mut visited = vec![vec![false; grid[0].len()]; grid.len()];
-    
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            if visited[y][x] {
-                continue;
-            }
-            let new_area = flood_fill(&grid, x, y, &mut visited);
-            areas.push(new_area);
-        }
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input.parse()
     }
 
-    Box::new(areas.into_par_iter().map(|a| {
-        sides(&a) * a.len() as i64
-    }).sum::<i64>())
-}
+    fn part1(grid: &Self::Input) -> Answer {
+        areas(grid).into_par_iter().map(|a| {
+            perimeter(&a) * a.len() as i64
+        }).sum::<i64>().into()
+    }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+    fn part2(grid: &Self::Input) -> Answer {
+        areas(grid).into_par_iter().map(|a| {
+            sides(&a) * a.len() as i64
+        }).sum::<i64>().into()
+    }
+}
 
-    const TEST_SMALL: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         AAAA
         BBCD
         BBCC
         EEEC"
-    };
-    const TEST_RESULT_SMALL: i64 = 140;
-    const TEST_RESULT_SMALL_P2: i64 = 80;
-    
-
-    const TEST_SMALL2: &str = indoc! {"
+    }).part1("140").part2("80"),
+    Example::new(indoc! {"
         OOOOO
         OXOXO
         OOOOO
         OXOXO
         OOOOO"
-    };
-    const TEST_RESULT_SMALL2: i64 = 772;
-    const TEST_RESULT_SMALL2_P2: i64 = 436;
-
-    
-    const TEST_INPUT: &str = indoc! {"
+    }).part1("772").part2("436"),
+    Example::new(indoc! {"
         RRRRIICCFF
         RRRRIICCCF
         VVRRRCCFFF
@@ -195,31 +145,48 @@ mod tests {
         MIIIIIJJEE
         MIIISIJEEE
         MMMISSJEEE"
-    };
+    }).part1("1930").part2("1206"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SMALL: &str = EXAMPLES[0].input;
+    const TEST_RESULT_SMALL: i64 = 140;
+    const TEST_RESULT_SMALL_P2: i64 = 80;
+    
+
+    const TEST_SMALL2: &str = EXAMPLES[1].input;
+    const TEST_RESULT_SMALL2: i64 = 772;
+    const TEST_RESULT_SMALL2_P2: i64 = 436;
+
+    
+    const TEST_INPUT: &str = EXAMPLES[2].input;
     const TEST_RESULT1: i64 = 1930;
     const TEST_RESULT2: i64 = 1206;
 
     // Test for part1
     #[test]
     fn test_part1_small() {
-        assert_eq!(part1(&String::from(TEST_SMALL)).to_string(), TEST_RESULT_SMALL.to_string());
-        assert_eq!(part1(&String::from(TEST_SMALL2)).to_string(), TEST_RESULT_SMALL2.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_SMALL).unwrap()), TEST_RESULT_SMALL.into());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_SMALL2).unwrap()), TEST_RESULT_SMALL2.into());
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT1.into());
     }
 
     // Test for part2
     #[test]
     fn test_part2_small() {
-        assert_eq!(part2(&String::from(TEST_SMALL)).to_string(), TEST_RESULT_SMALL_P2.to_string());
-        assert_eq!(part2(&String::from(TEST_SMALL2)).to_string(), TEST_RESULT_SMALL2_P2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_SMALL).unwrap()), TEST_RESULT_SMALL_P2.into());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_SMALL2).unwrap()), TEST_RESULT_SMALL2_P2.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
 }
\ No newline at end of file
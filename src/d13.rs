@@ -1,83 +1,71 @@
-use std::{ops::{Mul, Add, Sub}, sync::LazyLock};
+use std::sync::LazyLock;
+use indoc::indoc;
 use regex::Regex;
 
-static PARSE_REGEX_STR: &str = r"Button [A-Z]: X([+-]\d+), Y([+-]\d+)\r?\nButton [A-Z]: X([+-]\d+), Y([+-]\d+)\r?\nPrize: X=(\d+), Y=(\d+)";
-static PARSE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(PARSE_REGEX_STR).unwrap());
+use crate::{error::ParseError, examples::Example, geometry::Point, params::Params, solution::{Answer, Solution}};
 
-const PART_2_DIFF: i64 = 10_000_000_000_000;
+pub struct Solver;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Point {
-    x: i64,
-    y: i64,
-}
+static BUTTON_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^Button ([A-Z]): X([+-]\d+), Y([+-]\d+)$").unwrap());
+static PRIZE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^Prize: X=(\d+), Y=(\d+)$").unwrap());
 
 #[derive(Debug, Clone, Copy)]
-struct Puzzle {
+pub struct Puzzle {
     a: Point,
     b: Point,
     prize: Point,
 }
 
-impl Mul<i64> for Point {
-    type Output = Point;
-
-    fn mul(self, rhs: i64) -> Point {
-        Point {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
+/// The claw machines with how far part 2 moves every prize.
+pub struct Machines {
+    puzzles: Vec<Puzzle>,
+    offset: i64,
 }
 
-impl Add for Point {
-    type Output = Point;
-
-    fn add(self, rhs: Point) -> Point {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
+/// The claw machines, three lines each with blank lines between them.
+fn parse(input: &str) -> Result<Vec<Puzzle>, ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
+
+    lines.split(|line| line.trim().is_empty()).filter(|block| !block.is_empty()).map(|block| {
+        let &[a, b, prize] = block else {
+            let line = block.get(3).unwrap_or(&block[block.len() - 1]);
+            return Err(ParseError::new("A claw machine takes three lines").at(input, line).expected("two buttons and a prize"));
+        };
+
+        let puzzle = Puzzle {
+            a: point(input, a, &BUTTON_REGEX, "Button A: X+94, Y+34")?,
+            b: point(input, b, &BUTTON_REGEX, "Button B: X+22, Y+67")?,
+            prize: point(input, prize, &PRIZE_REGEX, "Prize: X=8400, Y=5400")?,
+        };
+
+        // the solution divides by these
+        if puzzle.b.y * puzzle.a.x - puzzle.b.x * puzzle.a.y == 0 {
+            return Err(ParseError::new("The buttons move in the same direction").at(input, b));
         }
-    }
+        if puzzle.b.x == 0 {
+            return Err(ParseError::new("Button B does not move along X").at(input, b));
+        }
+
+        Ok(puzzle)
+    }).collect()
 }
 
-impl Sub for Point {
-    type Output = Point;
+/// The point in the last two captures of `regex`, which has to match the whole `line`.
+fn point(input: &str, line: &str, regex: &Regex, example: &str) -> Result<Point, ParseError> {
+    let cap = regex.captures(line).ok_or_else(|| ParseError::new("Invalid claw machine line").at(input, line).expected(format!("a line like {}", example)))?;
+    let n = cap.len();
 
-    fn sub(self, rhs: Point) -> Point {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
+    Ok(Point { x: number(input, cap.get(n - 2).unwrap().as_str())?, y: number(input, cap.get(n - 1).unwrap().as_str())? })
 }
 
-fn parse(input: &String) -> Vec<Puzzle> {
-    PARSE_REGEX.captures_iter(input).map(|cap| {
-        Puzzle {
-            a: Point {
-                x: cap[1].parse().unwrap(),
-                y: cap[2].parse().unwrap(),
-            },
-            b: Point {
-                x: cap[3].parse().unwrap(),
-                y: cap[4].parse().unwrap(),
-            },
-            prize: Point {
-                x: cap[5].parse().unwrap(),
-                y: cap[6].parse().unwrap(),
-            },
-        }
-    }).collect()
+fn number(input: &str, n: &str) -> Result<i64, ParseError> {
+    n.parse().map_err(|_| ParseError::new(format!("{} is too large", n)).at(input, n))
 }
 
-fn solve_with_math(puzzle: Puzzle, larger: bool) -> Option<i64> {
-    let p = match larger {
-        true => Point {
-            x: puzzle.prize.x + PART_2_DIFF,
-            y: puzzle.prize.y + PART_2_DIFF,
-        }, 
-        false => puzzle.prize,
+fn solve_with_math(puzzle: Puzzle, offset: i64) -> Option<i64> {
+    let p = Point {
+        x: puzzle.prize.x + offset,
+        y: puzzle.prize.y + offset,
     };
     
     // 94 * a + 22 * b = 8400
@@ -122,38 +110,34 @@ fn solve_with_math(puzzle: Puzzle, larger: bool) -> Option<i64> {
     Some(a * 3 + b)
 }
 
-// Part 1: Count the number of peaks reachable from starting points
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let puzzles = parse(input);
-    
-    Box::new(
-        puzzles
-            .into_iter()
-            .map(|p| solve_with_math(p, false))
-            .filter_map(|x| x)
+impl Solution for Solver {
+    type Input = Machines;
+
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("part2_offset", "10000000000000")];
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        Ok(Machines { puzzles: parse(input)?, offset: params.get("part2_offset")? })
+    }
+
+    fn part1(machines: &Self::Input) -> Answer {
+        machines.puzzles
+            .iter()
+            .filter_map(|&p| solve_with_math(p, 0))
             .sum::<i64>()
-    )
-}
+            .into()
+    }
 
-// Part 2: Count the total number of paths from starting points to peaks
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let puzzles = parse(input);
-    
-    Box::new(
-        puzzles
-            .into_iter()
-            .map(|p| solve_with_math(p, true))
-            .filter_map(|x| x)
+    fn part2(machines: &Self::Input) -> Answer {
+        machines.puzzles
+            .iter()
+            .filter_map(|&p| solve_with_math(p, machines.offset))
             .sum::<i64>()
-    )
+            .into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         Button A: X+94, Y+34
         Button B: X+22, Y+67
         Prize: X=8400, Y=5400
@@ -169,12 +153,30 @@ mod tests {
         Button A: X+69, Y+23
         Button B: X+27, Y+71
         Prize: X=18641, Y=10279"
-    };
+    }).part1("480"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     const TEST_RESULT: i64 = 480;
 
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT.into());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = |input: &str| Solver::parse(input).err().map(|e| e.to_string());
+
+        assert!(error(&TEST_INPUT.replace("Y+67", "Y=67")).unwrap().contains("Invalid claw machine line"));
+        assert!(error(&TEST_INPUT.replace("Prize: X=12748, Y=12176\n", "")).unwrap().contains("three lines"));
+        assert!(error(&TEST_INPUT.replace("X+22, Y+67", "X+47, Y+17")).unwrap().contains("same direction"));
+        assert!(error(&TEST_INPUT.replace("X+22, Y+67", "X+0, Y+67")).unwrap().contains("does not move along X"));
+        assert!(error(&format!("{}\n\n", TEST_INPUT)).is_none());
     }
 }
\ No newline at end of file
@@ -1,185 +1,154 @@
-use std::{ops::{Add, Mul, Rem, Sub}, sync::LazyLock};
+use std::sync::LazyLock;
 use image::RgbImage;
+use indoc::indoc;
 use regex::Regex;
 
-static PARSE_REGEX_STR: &str = r"p=(\d+),(\d+) v=(-?\d+),(-?\d+)";
-static PARSE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(PARSE_REGEX_STR).unwrap());
-
+use crate::{artifacts::Kind, context::Context, error::ParseError, examples::Example, geometry::Point, params::Params, solution::{Answer, Solution}};
 
-#[cfg(test)] 
-const WIDTH: usize = 11;
-#[cfg(test)] 
-const HEIGHT: usize = 7;
+pub struct Solver;
 
-#[cfg(not(test))] 
-const WIDTH: usize = 101;
-#[cfg(not(test))] 
-const HEIGHT: usize = 103;
-
-static SIZE: Point = Point { x: WIDTH as i64, y: HEIGHT as i64 };
+static PARSE_REGEX_STR: &str = r"^p=(\d+),(\d+) v=(-?\d+),(-?\d+)$";
+static PARSE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(PARSE_REGEX_STR).unwrap());
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Point {
-    x: i64,
-    y: i64,
-}
 
 #[derive(Debug, Clone, Copy)]
-struct Robot {
+pub struct Robot {
     p: Point,
     v: Point,
 }
 
-impl Mul<i64> for Point {
-    type Output = Point;
-
-    fn mul(self, rhs: i64) -> Point {
-        Point {
-            x: self.x * rhs,
-            y: self.y * rhs,
-        }
-    }
+/// The robots with the size of the space they move in.
+pub struct Robots {
+    robots: Vec<Robot>,
+    size: Point,
 }
 
-impl Add for Point {
-    type Output = Point;
+fn quadrant(p: Point, size: Point) -> Option<usize> {
+    let w = size.x;
+    let h = size.y;
 
-    fn add(self, rhs: Point) -> Point {
-        Point {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
+    if p.y < h / 2 {
+        if p.x < w / 2 {
+            return Some(0);
+        } else if p.x > w / 2 {
+            return Some(1);
+        }
+    } else if p.y > h / 2 {
+        if p.x < w / 2 {
+            return Some(2);
+        } else if p.x > w / 2 {
+            return Some(3);
         }
     }
+
+    None
 }
 
-impl Sub for Point {
-    type Output = Point;
+/// A robot per line, every line has to be one.
+fn parse(input: &str) -> Result<Vec<Robot>, ParseError> {
+    input.trim_end().lines().map(|line| {
+        let cap = PARSE_REGEX.captures(line).ok_or_else(|| ParseError::new("Invalid robot").at(input, line).expected("a robot like p=0,4 v=3,-3"))?;
+        let point = |x: usize, y: usize| Ok(Point { x: number(input, &cap[x])?, y: number(input, &cap[y])? });
 
-    fn sub(self, rhs: Point) -> Point {
-        Point {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
+        Ok(Robot {
+            p: point(1, 2)?,
+            v: point(3, 4)?,
+        })
+    }).collect()
 }
 
-impl Rem<Point> for Point {
-    type Output = Point;
-
-    fn rem(self, rhs: Point) -> Point {
-        Point {
-            x: (self.x % rhs.x + rhs.x) % rhs.x,
-            y: (self.y % rhs.y + rhs.y) % rhs.y,
-        }
-    }
+fn number(input: &str, n: &str) -> Result<i64, ParseError> {
+    n.parse().map_err(|_| ParseError::new(format!("{} is too large", n)).at(input, n))
 }
 
-impl Point {
-    fn quadrant(&self) -> Option<usize> {
-        let w = WIDTH as i64;
-        let h = HEIGHT as i64;
+impl Solution for Solver {
+    type Input = Robots;
 
-        if self.y < h / 2 {
-            if self.x < w / 2 {
-                return Some(0);
-            } else if self.x > w / 2 {
-                return Some(1);
-            }
-        } else if self.y > h / 2 {
-            if self.x < w / 2 {
-                return Some(2);
-            } else if self.x > w / 2 {
-                return Some(3);
-            }
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("width", "101"), ("height", "103")];
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        let size = Point::new(params.get("width")?, params.get("height")?);
+        if size.x < 1 || size.y < 1 {
+            return Err(ParseError::new(format!("Invalid size {}x{}", size.x, size.y)));
         }
 
-        None
+        Ok(Robots { robots: parse(input)?, size })
     }
-}
 
-fn parse(input: &String) -> Vec<Robot> {
-    PARSE_REGEX.captures_iter(input).map(|cap| {
-        Robot {
-            p: Point {
-                x: cap[1].parse().unwrap(),
-                y: cap[2].parse().unwrap(),
-            },
-            v: Point {
-                x: cap[3].parse().unwrap(),
-                y: cap[4].parse().unwrap(),
-            },
-        }
-    }).collect()
-}
+    fn part1(input: &Self::Input) -> Answer {
+        let size = input.size;
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let robots = parse(input);
-
-    Box::new(
-        robots
+        input.robots
             .iter()
-            .filter_map(|robot| ((robot.p + (robot.v * 100)) % SIZE).quadrant())
+            .filter_map(|robot| quadrant((robot.p + (robot.v * 100)) % size, size))
             .fold(vec![0; 4], |mut acc, x| {
                 // sum the number of bots in each quadrant
-                acc[x] += 1 as i64;
+                acc[x] += 1;
                 acc
             })
             .into_iter()
-            .fold(1, |a, b| a * b)
-    )
-}
+            .product::<i64>()
+            .into()
+    }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let mut robots = parse(input);
-    
-    //make sure output directory exists
-    std::fs::create_dir_all("output").unwrap();
-
-    for i in 1..20_000 {
-        let mut grid = vec![vec![0; WIDTH]; HEIGHT];
-        
-        for bot in robots.iter_mut() {
-            bot.p = (bot.p + bot.v) % SIZE;
-            grid[bot.p.y as usize][bot.p.x as usize] = 1;
-            
-        }
-        
-        // check if there's a cluster of 5x5 filled cells
-        let mut found = false;
-        for y in 0..HEIGHT - 5 {
-            for x in 0..WIDTH - 5 {
-                let mut sum = 0;
-                for j in 0..5 {
-                    for i in 0..5 {
-                        sum += grid[y + j][x + i];
+    fn part2(input: &Self::Input) -> Answer {
+        Self::part2_with(input, &Context::headless())
+    }
+
+    fn part2_with(input: &Self::Input, context: &Context) -> Answer {
+        let mut robots = input.robots.clone();
+        let size = input.size;
+        let (width, height) = (size.x as usize, size.y as usize);
+
+        for i in 1..20_000 {
+            if context.cancelled() {
+                return Answer::Unsolved;
+            }
+            let mut grid = vec![vec![0; width]; height];
+
+            for bot in robots.iter_mut() {
+                bot.p = (bot.p + bot.v) % size;
+                grid[bot.p.y as usize][bot.p.x as usize] = 1;
+
+            }
+
+            // check if there's a cluster of 5x5 filled cells
+            let mut found = false;
+            for y in 0..height.saturating_sub(5) {
+                for x in 0..width.saturating_sub(5) {
+                    let mut sum = 0;
+                    for j in 0..5 {
+                        for i in 0..5 {
+                            sum += grid[y + j][x + i];
+                        }
+                    }
+                    if sum == 25 {
+                        found = true;
                     }
-                }
-                if sum == 25 {
-                    found = true;
                 }
             }
-        }
 
-        if found {
-            let mut img = RgbImage::new(WIDTH as u32, HEIGHT as u32);
-            for bot in robots.iter() {
-                img.put_pixel(bot.p.x as u32, bot.p.y as u32, image::Rgb([255, 255, 255]));
-            }
-            img.save(format!("output/d14_{:0>5}.png", i)).unwrap();
+            if found {
+                // keep a picture of the tree when the run keeps artifacts
+                context.emit_with(&format!("tree_{:0>5}", i), Kind::Png, |path| {
+                    let mut img = RgbImage::new(width as u32, height as u32);
+                    for bot in robots.iter() {
+                        img.put_pixel(bot.p.x as u32, bot.p.y as u32, image::Rgb([255, 255, 255]));
+                    }
+                    img.save(path).map_err(|e| e.to_string())
+                });
 
-            return Box::new(i);
+                return i.into();
+            }
         }
-    }
 
-    panic!("No solution found");
+        // no tree in all the time the robots take to come back to where they started
+        Answer::Unsolved
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         p=0,4 v=3,-3
         p=6,3 v=-1,-3
         p=10,3 v=-1,2
@@ -192,12 +161,35 @@ mod tests {
         p=7,3 v=-1,2
         p=2,4 v=2,-3
         p=9,5 v=-3,-3"
-    };
+    }).part1("12").params(&[("width", "11"), ("height", "7")]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
     const TEST_RESULT: i64 = 12;
 
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT.to_string());
+        assert_eq!(Solver::part1(&EXAMPLES[0].parse::<Solver>().unwrap()), TEST_RESULT.into());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = EXAMPLES[0].input;
+
+        assert!(parse(&input.replace("p=6,3 v=-1,-3", "p=6,3 v=-1;-3")).unwrap_err().to_string().contains("Invalid robot"));
+        assert!(parse(&input.replace("p=6,3 v=-1,-3", "")).unwrap_err().to_string().contains("Invalid robot"));
+        assert_eq!(parse(&format!("{}\n\n", input)).unwrap().len(), 12);
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let context = Context::headless();
+        context.cancel();
+
+        assert_eq!(Solver::part2_with(&EXAMPLES[0].parse::<Solver>().unwrap(), &context), Answer::Unsolved);
     }
 }
\ No newline at end of file
@@ -1,8 +1,15 @@
-type Grid = Vec<Vec<Pos>>;
-type Instructions = Vec<Instruction>;
+use indoc::indoc;
+use tracing::{enabled, trace, Level};
+
+use crate::{error::ParseError, examples::Example, geometry::{Direction, Point}, grid::Grid, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+type Warehouse = Grid<Pos>;
+type Instructions = Vec<Direction>;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Pos {
+pub enum Pos {
     Wall,
     Box,
     Empty,
@@ -11,68 +18,52 @@ enum Pos {
 }
 
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum Instruction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 #[derive(Clone, Copy, Debug)]
-struct Robot {
-    x: i64,
-    y: i64,
+pub struct Robot {
+    pos: Point,
 }
 
 impl Robot {
-    fn new(x: usize, y: usize) -> Self {
-        Self { x: x as i64, y: y as i64 }
+    fn new(pos: Point) -> Self {
+        Self { pos }
     }
 
-    fn try_move(&mut self, grid: &mut Grid, instruction: Instruction) -> Result<(),String> {
-        let (dx, dy) = instruction.to_direction();
-        let mut x = self.x + dx;
-        let mut y = self.y + dy;
-        let first = try_get(x, y, grid)?;
+    fn try_move(&mut self, grid: &mut Warehouse, instruction: Direction) -> Result<(),String> {
+        let step = instruction.vector();
+        let mut pos = self.pos + step;
+        let first = try_get(pos, grid)?;
         
         if first == Pos::Wall {
             return Err(String::from("Wall"));
         }
 
         if first == Pos::Empty {
-            self.x = x;
-            self.y = y;
+            self.pos = pos;
             return Ok(());
         }
 
-        let first = (x, y);
+        let first = pos;
         
-        x += dx;
-        y += dy;
+        pos += step;
 
         let space = loop {
-            match try_get(x, y, &grid)? {
+            match try_get(pos, grid)? {
                 Pos::Wall => return Err(String::from("Wall after box")),
-                Pos::Empty => break (x, y),
+                Pos::Empty => break pos,
                 _ => {
-                    x += dx;
-                    y += dy;
+                    pos += step;
                 },
             }
         };
 
         // set the first box to empty
-        let (x, y) = first;
-        grid[y as usize][x as usize] = Pos::Empty;
+        grid.set(first, Pos::Empty);
         
         // move the robot
-        self.x = x;
-        self.y = y;
+        self.pos = first;
 
         // set the empty space to a box
-        let (x, y) = space;
-        grid[y as usize][x as usize] = Pos::Box;
+        grid.set(space, Pos::Box);
         
         Ok(())
 
@@ -100,231 +91,176 @@ impl TryFrom<char> for Pos {
     }
 }
 
-impl Instruction {
-    fn from_char(c: char) -> Option<Instruction> {
-        match c {
-            '^' => Some(Instruction::Up),
-            'v' => Some(Instruction::Down),
-            '<' => Some(Instruction::Left),
-            '>' => Some(Instruction::Right),
-            _ => None,
-        }
-    }
-
-    fn to_direction(&self) -> (i64, i64) {
-        match self {
-            Instruction::Up => (0, -1),
-            Instruction::Down => (0, 1),
-            Instruction::Left => (-1, 0),
-            Instruction::Right => (1, 0),
-        }
-    }
+fn try_get(pos: Point, grid: &Warehouse) -> Result<Pos,String> {
+    grid.get(pos).copied().ok_or_else(|| String::from("Out of bounds"))
 }
 
-impl TryFrom<char> for Instruction {
-    type Error = ();
-
-    fn try_from(c: char) -> Result<Self, Self::Error> {
-        Self::from_char(c).ok_or(())
-    }
-}
 
-fn try_get(x: i64, y: i64, grid: &Grid) -> Result<Pos,String> {
-    if x < 0 || y < 0 {
-        return Err(String::from("Out of bounds"));
-    }
-    if y as usize >= grid.len() || x as usize >= grid[y as usize].len() {
-        return Err(String::from("Out of bounds"));
-    }
-    Ok(grid[y as usize][x as usize])
-}
-
-
-fn parse(input: &String) -> (Grid, Instructions, Robot) {
+fn parse(input: &str) -> Result<(Warehouse, Instructions, Robot), ParseError> {
     // split once by double newline
     let (grid_str, instructions_str) = input
         .split_once("\n\n")
-        .or(input.split_once("\r\n\r\n"))
-        .unwrap();
+        .or_else(|| input.split_once("\r\n\r\n"))
+        .ok_or_else(|| ParseError::new("Expected a map and instructions separated by an empty line"))?;
 
-    let mut robot: Option<Robot> = None;
-    let mut grid: Grid = Vec::new();
     let mut instructions: Instructions = Vec::new();
 
-    for (y, line) in grid_str.lines().enumerate() {
-        let mut row = Vec::new();
-        for (x, c) in line.chars().enumerate() {
-            if c == '@' && robot.is_none() {
-                robot = Some(Robot::new(x, y));
-                row.push(Pos::Empty);
-            } else {
-                row.push(c.try_into().unwrap());
-            }
+    let chars: Grid<char> = grid_str.parse()?;
+    let pos = chars.find(&'@').ok_or_else(|| ParseError::new("No robot found"))?;
+    let robot = Robot::new(pos);
+
+    let mut robots = 0;
+    let grid = Grid::parse(grid_str, |c| match c {
+        '@' if robots == 0 => {
+            robots += 1;
+            Ok(Pos::Empty)
         }
-        grid.push(row);
+        _ => c.try_into().map_err(|_| ParseError::new(format!("Invalid map character {}", c)).expected("one of # O . @")),
+    })?;
+
+    for (i, c) in instructions_str.char_indices().filter(|(_, c)| !c.is_whitespace()) {
+        let instruction = Direction::try_from(c).map_err(|e| {
+            ParseError::new(e).at(input, &instructions_str[i..i + c.len_utf8()]).expected("one of ^ > v <")
+        })?;
+        instructions.push(instruction);
     }
 
-    for c in instructions_str.chars() {
-        if let Ok(i) = Instruction::try_from(c) {
-            instructions.push(i);
+    Ok((grid, instructions, robot))
+}
+
+fn draw_map(grid: &Warehouse, robot: &Robot) -> String {
+    let mut out = String::new();
+
+    for (y, row) in grid.rows().enumerate() {
+        out.push('\n');
+        for (x, p) in row.iter().enumerate() {
+            out.push(match p {
+                _ if robot.pos == Point::new(x as i64, y as i64) => '@',
+                Pos::Wall => '#',
+                Pos::Box => 'O',
+                Pos::Empty => '.',
+                Pos::LeftBox => '[',
+                Pos::RightBox => ']',
+            });
         }
     }
 
-    (grid, instructions, robot.unwrap())
+    out
 }
 
-#[cfg(test)]
-fn print_map(grid: &Grid, robot: &Robot) {
-    for (y, row) in grid.iter().enumerate() {
-        for (x, p) in row.iter().enumerate() {
-            if robot.x as usize == x && robot.y as usize == y {
-                print!("@");
+fn scale_up(grid: Warehouse) -> Warehouse {
+    let mut cells = Vec::new();
+    for row in grid.rows() {
+        for &p in row {
+            if p == Pos::Box {
+                cells.push(Pos::LeftBox);
+                cells.push(Pos::RightBox);
             } else {
-                match p {
-                    Pos::Wall => print!("#"),
-                    Pos::Box => print!("O"),
-                    Pos::Empty => print!("."),
-                    Pos::LeftBox => print!("["),
-                    Pos::RightBox => print!("]"),
-                }
+                cells.push(p);
+                cells.push(p);
             }
         }
-        println!();
     }
+    Grid::from_vec(grid.width() * 2, grid.height(), cells)
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let (mut grid, instructions, mut robot) = parse(input);
+impl Solution for Solver {
+    type Input = (Warehouse, Instructions, Robot);
 
-    #[cfg(test)]
-    {
-        for i in instructions {
-            println!("Instruction: {:?}", i);
-
-            if let Err(str) = robot.try_move(&mut grid, i) {
-                println!("Error: {}", str);
-            }
-        
-            print_map(&grid, &robot);
-            println!();
-        }
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        parse(input)
     }
 
-    #[cfg(not(test))]
-    {
+    fn part1(input: &Self::Input) -> Answer {
+        let (mut grid, instructions, mut robot) = input.clone();
+
         for i in instructions {
-            let _ = robot.try_move(&mut grid, i);
+            if let Err(e) = robot.try_move(&mut grid, i) {
+                trace!(instruction = ?i, "{}", e);
+            }
+
+            // drawing the whole map for every instruction is only worth it when someone reads it
+            if enabled!(Level::TRACE) {
+                trace!(instruction = ?i, "{}", draw_map(&grid, &robot));
+            }
         }
-    }
 
-    let mut gps = 0;
-    for (y, row) in grid.into_iter().enumerate() {
-        for (x, p) in row.into_iter().enumerate() {
+        let mut gps = 0;
+        for (pos, &p) in grid.iter() {
             if p == Pos::Box {
-                gps += 100 * y + x;
+                gps += 100 * pos.y + pos.x;
             }
         }
+
+        gps.into()
     }
 
-    Box::new(
-        i64::try_from(gps).unwrap()
-    )
-}
+    fn part2(input: &Self::Input) -> Answer {
+        let (grid, instructions, mut robot) = input.clone();
+        let mut grid = scale_up(grid);
+        robot.pos.x *= 2;
 
-fn scale_up(grid: Vec<Vec<Pos>>) -> Vec<Vec<Pos>> {
-    let mut new_grid = Vec::new();
-    for row in grid {
-        let mut new_row = Vec::new();
-        for p in row {
-            if p == Pos::Box {
-                new_row.push(Pos::LeftBox);
-                new_row.push(Pos::RightBox);
-            } else {
-                new_row.push(p);
-                new_row.push(p);
+
+        fn try_move(grid: &Warehouse, pos: Point, instruction: Direction) -> Result<Warehouse,String> {
+            let from = try_get(pos, grid)?;
+            let target = pos + instruction.vector();
+
+            let to = try_get(target, grid)?;
+
+            match to {
+                Pos::Wall => Err("Cannot move into wall".to_string()),
+                Pos::Empty => {
+                    let mut new_grid = grid.clone();
+                    new_grid.set(pos, Pos::Empty);
+                    new_grid.set(target, from);
+                    Ok(new_grid)
+                },
+                Pos::LeftBox => {
+                    let mut new_grid = try_move(grid, target, instruction)?;
+                    new_grid.set(pos, Pos::Empty);
+                    new_grid.set(target, from);
+
+                    if instruction.is_vertical() {                    
+                        new_grid = try_move(&new_grid, target + Direction::Right.vector(), instruction)?;
+                    }
+                    Ok(new_grid)
+                },
+                Pos::RightBox => {                
+                    let mut new_grid = try_move(grid, target, instruction)?;
+                    new_grid.set(pos, Pos::Empty);
+                    new_grid.set(target, from);
+
+                    if instruction.is_vertical() {
+                        new_grid = try_move(&new_grid, target + Direction::Left.vector(), instruction)?;
+                    }
+
+                    Ok(new_grid)
+                },
+                _ => panic!("Unsupported position type for part 2")
             }
         }
-        new_grid.push(new_row);
-    }
-    new_grid
-}
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let (grid, instructions, mut robot) = parse(input);
-    let mut grid = scale_up(grid);
-    robot.x *= 2;
-
-
-    fn try_move(grid: &Vec<Vec<Pos>>, (x,y): (i64,i64), instruction: &Instruction) -> Result<Vec<Vec<Pos>>,String> {
-        let from = try_get(x, y, &grid)?;
-        let (dx, dy) = instruction.to_direction();
-        let tox = x + dx;
-        let toy = y + dy;
-
-        let to = try_get(tox, toy, &grid)?;
-
-        match to {
-            Pos::Wall => return Err("Cannot move into wall".to_string()),
-            Pos::Empty => {
-                let mut new_grid = grid.clone();
-                new_grid[y as usize][x as usize] = Pos::Empty;
-                new_grid[toy as usize][tox as usize] = from;
-                Ok(new_grid)
-            },
-            Pos::LeftBox => {
-                let mut new_grid = try_move(grid, (tox, toy), instruction)?;
-                new_grid[y as usize][x as usize] = Pos::Empty;
-                new_grid[toy as usize][tox as usize] = from;
-
-                if *instruction == Instruction::Up || *instruction == Instruction::Down {                    
-                    new_grid = try_move(&new_grid, (x + 1,toy), instruction)?;
-                }
-                Ok(new_grid)
-            },
-            Pos::RightBox => {                
-                let mut new_grid = try_move(grid, (tox, toy), instruction)?;
-                new_grid[y as usize][x as usize] = Pos::Empty;
-                new_grid[toy as usize][tox as usize] = from;
-
-                if *instruction == Instruction::Up || *instruction == Instruction::Down {
-                    new_grid = try_move(&new_grid, (x - 1,toy), instruction)?;
-                }
-
-                Ok(new_grid)
-            },
-            _ => panic!("Unsupported position type for part 2")
+        for instr in instructions {
+            if let Ok(new_grid) = try_move(&grid, robot.pos, instr) {
+                robot.pos += instr.vector();
+                grid = new_grid;
+            }
         }
-    }
 
-    for instr in instructions {
-        if let Ok(new_grid) = try_move(&grid, (robot.x, robot.y), &instr) {
-            robot.x += instr.to_direction().0;
-            robot.y += instr.to_direction().1;
-            grid = new_grid;
-        }
-    }
 
-    
-    let mut gps = 0;
-    for (y, row) in grid.into_iter().enumerate() {
-        for (x, p) in row.into_iter().enumerate() {
+        let mut gps = 0;
+        for (pos, &p) in grid.iter() {
             if p == Pos::LeftBox {
-                gps += 100 * y + x;
+                gps += 100 * pos.y + pos.x;
             }
         }
-    }
 
-    Box::new(
-        i64::try_from(gps).unwrap()
-    )
+        gps.into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-
-    const TEST_INPUT_SMALL: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         ########
         #..O.O.#
         ##@.O..#
@@ -335,10 +271,8 @@ mod tests {
         ########
 
         <^^>>>vv<v>>v<<"
-    };
-
-    const TEST_RESULT_SMALL: i64 = 2028;
-    const TEST_INPUT: &str = indoc! {"
+    }).part1("2028"),
+    Example::new(indoc! {"
         ##########
         #..O..O.O#
         #......O.#
@@ -360,23 +294,33 @@ mod tests {
         <><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
         ^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
         v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"
-    };
+    }).part1("10092").part2("9021"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT_SMALL: &str = EXAMPLES[0].input;
+
+    const TEST_RESULT_SMALL: i64 = 2028;
+    const TEST_INPUT: &str = EXAMPLES[1].input;
     const TEST_RESULT: i64 = 10092;
     const TEST_RESULT2: i64 = 9021;
 
     // Test for part1
     #[test]
     fn test_part1_small() {
-        assert_eq!(part1(&String::from(TEST_INPUT_SMALL)).to_string(), TEST_RESULT_SMALL.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT_SMALL).unwrap()), TEST_RESULT_SMALL.into());
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT.into());
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT2.into());
     }
 }
\ No newline at end of file
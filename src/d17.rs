@@ -1,5 +1,13 @@
 use std::str::FromStr;
 
+use indoc::indoc;
+use itertools::Itertools;
+use tracing::debug;
+
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 struct Instruction (OpCode, u8);
 
@@ -15,6 +23,13 @@ enum OpCode {
     CDv = 7, // regA / comboOp^2 -> regC
 }
 
+impl OpCode {
+    /// Whether the operand is a combo operand, the others are literal.
+    fn takes_combo(self) -> bool {
+        matches!(self, OpCode::ADv | OpCode::Bst | OpCode::Out | OpCode::BDv | OpCode::CDv)
+    }
+}
+
 impl TryFrom<i64> for OpCode {
     type Error = ();
 
@@ -34,7 +49,7 @@ impl TryFrom<i64> for OpCode {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
-struct Program {
+pub struct Program {
     reg_a: i64,
     reg_b: i64,
     reg_c: i64,
@@ -44,20 +59,50 @@ struct Program {
     code: Vec<i64>,
 }
 
+/// The value after `label` on the next line, like the `729` in `Register A: 729`.
+fn field<'a>(s: &'a str, line: Option<&'a str>, label: &str) -> Result<&'a str, ParseError> {
+    let line = line.ok_or_else(|| ParseError::new("Unexpected end of input").expected(label))?;
+
+    line.strip_prefix(label)
+        .map(str::trim)
+        .ok_or_else(|| ParseError::new(format!("Missing '{}'", label)).at(s, line).expected(label))
+}
+
+fn number(s: &str, n: &str) -> Result<i64, ParseError> {
+    n.parse().map_err(|_| ParseError::new(format!("{} is not a number", n)).at(s, n))
+}
+
 impl FromStr for Program {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut lines = s.lines();
-        let reg_a = lines.next().unwrap().split_whitespace().last().unwrap().parse().unwrap();
-        let reg_b = lines.next().unwrap().split_whitespace().last().unwrap().parse().unwrap();
-        let reg_c = lines.next().unwrap().split_whitespace().last().unwrap().parse().unwrap();
-        let code = lines.skip(1).next().unwrap().split_whitespace().last().unwrap().split(',').map(|x| x.parse().unwrap()).collect::<Vec<i64>>();
-        let instructions = code.iter()
-            .collect::<Vec<&i64>>()
+        let reg_a = number(s, field(s, lines.next(), "Register A:")?)?;
+        let reg_b = number(s, field(s, lines.next(), "Register B:")?)?;
+        let reg_c = number(s, field(s, lines.next(), "Register C:")?)?;
+        let program = field(s, lines.nth(1), "Program:")?;
+        let code = program.split(',').map(|x| number(s, x)).collect::<Result<Vec<i64>, _>>()?;
+
+        if code.len() % 2 != 0 {
+            return Err(ParseError::new("The program has an opcode without an operand").at(s, program));
+        }
+
+        let instructions = code
             .chunks(2)
-            .map(|c| Instruction((*c[0]).try_into().unwrap(), (*c[1]).try_into().unwrap()))
-            .collect();
+            .zip(program.split(',').tuples())
+            .map(|(c, (op_text, operand_text))| {
+                let op: OpCode = c[0].try_into().map_err(|_| ParseError::new(format!("Invalid opcode {}", c[0])).at(s, op_text).expected("0 to 7"))?;
+                let operand = match c[1] {
+                    0..=7 => c[1] as u8,
+                    _ => return Err(ParseError::new(format!("Invalid operand {}", c[1])).at(s, operand_text).expected("0 to 7")),
+                };
+                // 7 is reserved as a combo operand, it does not stand for a value
+                if op.takes_combo() && operand == 7 {
+                    return Err(ParseError::new("Invalid combo operand 7").at(s, operand_text).expected("0 to 6"));
+                }
+                Ok(Instruction(op, operand))
+            })
+            .collect::<Result<_, ParseError>>()?;
 
         Ok(Program {
             reg_a,
@@ -79,7 +124,7 @@ impl Program {
             4 => self.reg_a,
             5 => self.reg_b,
             6 => self.reg_c,
-            _ => panic!("Invalid combo value"),
+            _ => unreachable!("parsing rejects the combo operand {}", combo),
         }
     }
 
@@ -151,114 +196,117 @@ impl Program {
             }
         }).collect::<Vec<String>>().join("\n")
     }
+}
 
-    fn run_for(&mut self, output: &Vec<i64>, id: i64) {
-        self.output.clear();
-        
-        let mut next_to_match = output.iter().peekable();
 
-        while self.pc < self.instructions.len() {
-            let Instruction(op, arg) = self.instructions[self.pc];
+impl Solution for Solver {
+    type Input = Program;
 
-            match op {
-                OpCode::ADv => self.reg_a /= 2i64.pow(self.get_combo_value(arg) as u32),
-                OpCode::Bxl => self.reg_b ^= arg as i64,
-                OpCode::Bst => self.reg_b = self.get_combo_value(arg) % 8,
-                OpCode::Jnz => if self.reg_a != 0 { self.pc = arg as usize; continue; },
-                OpCode::Bxc => self.reg_b ^= self.reg_c,
-                OpCode::Out => {
-                    let o = self.get_combo_value(arg) % 8;
-                    if let Some(&n) = next_to_match.peek() {
-                        if o != *n {
-                            return;
-                        }
-                        self.output.push(o);
-                        next_to_match.next();
-                    } else {
-                        return;
-                    }
-                },
-                OpCode::BDv => self.reg_b = self.reg_a / 2i64.pow(self.get_combo_value(arg) as u32),
-                OpCode::CDv => self.reg_c = self.reg_a / 2i64.pow(self.get_combo_value(arg) as u32),
-            }
-            
-            self.pc += 1;
-        }
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input.parse()
     }
-}
-
-
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let mut program: Program = input.parse().unwrap();
-    let steps = program.run();
-    println!("{:?} steps", steps);
 
-    Box::new(program.output.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(","))
-}
+    fn part1(program: &Self::Input) -> Answer {
+        let mut program = program.clone();
+        let steps = program.run();
+        debug!(steps, "program halted");
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-
-    let mut program: Program = input.parse().unwrap();
-    let code = program.code.clone();
-    let mut run = |n: i64| {
-        program.reg_a = n;
-        program.pc = 0;
-        program.output.clear();
-        program.run();
-        program.output[0]
-    };
-
-    let mut find_for = |base: i64, n: i64| {
-        (0..=100).find(|a| {
-            run(base | *a) == n
-        }).unwrap()
-    };
-
-    let mut n = 0;
-    for c in code.iter().rev() {
-        n = n << 3 | find_for(n << 3, *c);
+        program.output.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(",").into()
     }
 
-    run(n);
+    fn part2(program: &Self::Input) -> Answer {
+        // the search relies on the program shifting A by 3 bits for every output
+        debug!("program:\n{}", program.transpile());
+
+        let mut program = program.clone();
+        let code = program.code.clone();
+        let mut run = |n: i64| {
+            program.reg_a = n;
+            program.pc = 0;
+            program.output.clear();
+            program.run();
+            program.output[0]
+        };
+
+        let mut find_for = |base: i64, n: i64| {
+            (0..=100).find(|a| {
+                run(base | *a) == n
+            }).unwrap()
+        };
+
+        let mut n = 0;
+        for c in code.iter().rev() {
+            n = n << 3 | find_for(n << 3, *c);
+        }
 
-    println!("Program OUT: {:?}", program.output);
+        run(n);
 
-    Box::new(n)
-}
+        debug!(a = n, output = ?program.output, "program reproduces itself");
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+        n.into()
+    }
+}
 
-    const TEST_INPUT_1: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         Register A: 729
         Register B: 0
         Register C: 0
 
         Program: 0,1,5,4,3,0"
-    };
-    const TEST_RESULT_1: &str = "4,6,3,5,6,3,5,2,1,0";
-
-    const TEST_INPUT_2: &str = indoc! {"
+    }).part1("4,6,3,5,6,3,5,2,1,0"),
+    Example::new(indoc! {"
         Register A: 2024
         Register B: 0
         Register C: 0
 
         Program: 0,3,5,4,3,0"
-    };
+    }).part2("117440"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT_1: &str = EXAMPLES[0].input;
+    const TEST_RESULT_1: &str = "4,6,3,5,6,3,5,2,1,0";
+
+    const TEST_INPUT_2: &str = EXAMPLES[1].input;
     const TEST_RESULT_2: i64 = 117440;
 
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT_1)).to_string(), TEST_RESULT_1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT_1).unwrap()), TEST_RESULT_1.into());
     }
 
     // Test for part2
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT_2)).to_string(), TEST_RESULT_2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT_2).unwrap()), TEST_RESULT_2.into());
+    }
+
+    #[test]
+    fn test_transpile() {
+        let program = Solver::parse(TEST_INPUT_2).unwrap();
+        assert_eq!(program.transpile(), "reg_a = reg_a >> 3;\noutput.push(reg_a % 8);\nif reg_a != 0 { GOTO 0 };");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = Solver::parse(&TEST_INPUT_1.replace("0,1,5", "0,1,8")).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid opcode 8 at line 5, column 14 (expected 0 to 7)");
+
+        let error = Solver::parse(&TEST_INPUT_1.replace("0,1,5,4", "5,200")).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid operand 200 at line 5, column 12 (expected 0 to 7)");
+
+        // 7 is a valid literal operand but not a combo one
+        assert!(Solver::parse(&TEST_INPUT_1.replace("0,1,5,4", "1,7,5,4")).is_ok());
+        let error = Solver::parse(&TEST_INPUT_1.replace("0,1,5,4", "5,7")).unwrap_err();
+        assert_eq!(error.to_string(), "Invalid combo operand 7 at line 5, column 12 (expected 0 to 6)");
+
+        let error = Solver::parse(&TEST_INPUT_1.replace("B: 0", "B: zero")).unwrap_err();
+        assert_eq!(error.location.map(|l| (l.line, l.column)), Some((2, 13)));
     }
     
 }
\ No newline at end of file
@@ -1,4 +1,9 @@
-use std::collections::VecDeque;
+use indoc::indoc;
+use tracing::debug;
+
+use crate::{error::ParseError, examples::Example, geometry::Point, grid::Grid, params::Params, search, solution::{Answer, Solution}};
+
+pub struct Solver;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Tile {
@@ -6,143 +11,101 @@ enum Tile {
     Blocked
 }
 
-#[derive(Clone, PartialEq, Eq, Debug)]
-struct Grid {
-    grid: Vec<Vec<Tile>>,
+type Coord = Point;
+
+/// The falling bytes with the size of the memory space and how many bytes fall before part 1.
+pub struct Memory {
+    coords: Vec<Coord>,
     width: usize,
-    height: usize
+    height: usize,
+    bytes: usize,
 }
 
-fn make_coords_iter(input: &String) -> impl Iterator<Item=(usize, usize)> + '_ {
+fn parse_coords(input: &str) -> Result<Vec<Coord>, ParseError> {
     input.lines().map(|line| {
-        let (x, y) = line.split_once(",").unwrap();
-        (x.parse::<usize>().unwrap(), y.parse::<usize>().unwrap())
-    })
+        let error = || ParseError::new("Invalid coordinate").at(input, line).expected("a coordinate like 3,4");
+        let (x, y) = line.split_once(",").ok_or_else(error)?;
+        Ok(Point::new(x.parse().map_err(|_| error())?, y.parse().map_err(|_| error())?))
+    }).collect()
 }
 
-fn make_grid(input: &String, width: usize, height: usize, bytes: usize) -> Grid {
-    let mut tiles = vec![vec![Tile::Empty; width]; height];
-    let mut coords = make_coords_iter(input);
+fn make_grid(coords: &[Coord], width: usize, height: usize, bytes: usize) -> Grid<Tile> {
+    let mut grid = Grid::new(width, height, Tile::Empty);
 
-    for _ in 0..bytes {
-        if let Some((x, y)) = coords.next() {
-            tiles[y][x] = Tile::Blocked;
-        }
+    for &p in coords.iter().take(bytes) {
+        grid.set(p, Tile::Blocked);
     }
 
-    Grid {
-        grid: tiles,
-        width,
-        height
-    }
+    grid
 }
 
-fn find_path_cost(grid: &Grid, count: &mut usize) -> Option<i64> {
-    let width = grid.width;
-    let height = grid.height;
-
-    let start = (0, 0);
-    let end = (width - 1, height - 1);
+fn find_path_cost(grid: &Grid<Tile>) -> Option<usize> {
+    let end = Point::new(grid.width() as i64 - 1, grid.height() as i64 - 1);
 
-    let mut stack = VecDeque::new();
-    stack.push_back(start);
+    let found = search::bfs(
+        Point::ZERO,
+        |&p| grid.neighbours4(p).filter(|&n| grid.get(n) == Some(&Tile::Empty)).collect::<Vec<_>>(),
+        |&p| p == end,
+    );
 
-    let mut costs = vec![vec![std::i64::MAX; width]; height];
-    costs[start.1][start.0] = 0;
-
-    
-    while let Some((x, y)) = stack.pop_front() {
-        let cost = costs[y][x];
-        *count += 1;
-        if (x, y) == end {
-            return Some(cost);
-        }
-
-        for (dx, dy) in [(1, 0), (0, 1)].iter() {
-            let nx = x as i64 + dx;
-            let ny = y as i64 + dy;
-            if nx >= 0 && nx < width as i64 && ny >= 0 && ny < height as i64 {
-                let nx = nx as usize;
-                let ny = ny as usize;
-                if grid.grid[ny][nx] == Tile::Empty && cost + 1 < costs[ny][nx] {
-                    costs[ny][nx] = cost + 1;
-                    stack.push_front((nx, ny));
-                }
-            }
-        }
-
-        for (dx, dy) in [(-1, 0), (0, -1)].iter() {
-            let nx = x as i64 + dx;
-            let ny = y as i64 + dy;
-            if nx >= 0 && nx < width as i64 && ny >= 0 && ny < height as i64 {
-                let nx = nx as usize;
-                let ny = ny as usize;
-                if grid.grid[ny][nx] == Tile::Empty && cost + 1 < costs[ny][nx] {
-                    costs[ny][nx] = cost + 1;
-                    stack.push_back((nx, ny));
-                }
-            }
-        }
-    }
-
-    None
+    found.map(|found| found.cost)
 }
 
+/// The fewest steps to the exit once the first bytes fell, `None` when they cut it off.
+fn solve_part1(memory: &Memory) -> Option<usize> {
+    let grid = make_grid(&memory.coords, memory.width, memory.height, memory.bytes);
 
-// these are in separate functions because test and real input have different arguments that aren't specified in the input
-fn solve_part1(input: &String, width: usize, height: usize, bytes: usize) -> i64 {
-    let grid = make_grid(input, width, height, bytes);
-    let mut count = 0usize;
-    
-    let r = find_path_cost(&grid, &mut count).unwrap();
-    println!("Found solution after {} iterations", count);
-    r
-
+    find_path_cost(&grid)
 }
 
-fn solve_part2(input: &String, width: usize, height: usize, starting: usize) -> String {
-    let tiles = vec![vec![Tile::Empty; width]; height];
-    let coords = make_coords_iter(input);
-
-    let mut map = Grid {
-        grid: tiles,
-        width,
-        height
-    };
+/// The first byte cutting off the exit, `None` when the exit stays reachable.
+fn solve_part2(memory: &Memory) -> Option<String> {
+    let mut map = Grid::new(memory.width, memory.height, Tile::Empty);
+    let starting = memory.bytes;
 
-    let mut count = 0usize;
     let mut it = 0;
 
-    for (x,y) in coords  {
-        map.grid[y][x] = Tile::Blocked;
+    for &p in &memory.coords {
+        map.set(p, Tile::Blocked);
         it += 1;
         if it < starting {
             continue;
         }
         
-        if find_path_cost(&map, &mut count).is_none() {
-            println!("Found solution after {} iterations", count);
-            return format!("{},{}", x, y);
+        if find_path_cost(&map).is_none() {
+            debug!(bytes = it, "path cut off");
+            return Some(p.to_string());
         }
     }
-    
-    panic!("No solution found");
-}
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    Box::new(solve_part1(input, 71, 71, 1024))
+    None
 }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    Box::new(solve_part2(input, 71, 71, 1024))
-}
+impl Solution for Solver {
+    type Input = Memory;
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("width", "71"), ("height", "71"), ("bytes", "1024")];
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        Ok(Memory {
+            coords: parse_coords(input)?,
+            width: params.get("width")?,
+            height: params.get("height")?,
+            bytes: params.get("bytes")?,
+        })
+    }
+
+    fn part1(memory: &Self::Input) -> Answer {
+        solve_part1(memory).map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2(memory: &Self::Input) -> Answer {
+        solve_part2(memory).map_or(Answer::Unsolved, Answer::from)
+    }
+}
 
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         5,4
         4,2
         4,5
@@ -168,20 +131,35 @@ mod tests {
         0,5
         1,6
         2,0"
-    };
-    const TEST_RESULT_1: i64 = 22;
+    }).part1("22").part2("6,1").params(&[("width", "7"), ("height", "7"), ("bytes", "12")]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_RESULT_1: usize = 22;
     const TEST_RESULT_2: &str = "6,1";
 
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(solve_part1(&String::from(TEST_INPUT), 7, 7, 12), TEST_RESULT_1);
+        assert_eq!(solve_part1(&EXAMPLES[0].parse::<Solver>().unwrap()), Some(TEST_RESULT_1));
     }
 
     // Test for part2
     #[test]
     fn test_part2() {
-        assert_eq!(solve_part2(&String::from(TEST_INPUT), 7, 7, 12), TEST_RESULT_2.to_string());
+        assert_eq!(solve_part2(&EXAMPLES[0].parse::<Solver>().unwrap()), Some(TEST_RESULT_2.to_string()));
+    }
+
+    #[test]
+    fn test_unsolved() {
+        // the first byte already cuts off the exit, and the exit never gets cut off
+        let blocked = Solver::parse_with("1,0\n0,1", &Params::new(&[("width", "2"), ("height", "2"), ("bytes", "2")])).unwrap();
+        assert_eq!(Solver::part1(&blocked), Answer::Unsolved);
+        let open = Solver::parse_with("1,1\n0,2", &Params::new(&[("width", "3"), ("height", "3"), ("bytes", "1")])).unwrap();
+        assert_eq!(Solver::part2(&open), Answer::Unsolved);
     }
     
 }
\ No newline at end of file
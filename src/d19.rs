@@ -1,77 +1,75 @@
 use std::collections::HashMap;
 
-use rayon::iter::{ParallelBridge, ParallelIterator};
+use indoc::indoc;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-fn parse(input: &String) -> (Vec<&str>, impl Iterator<Item=&str>) {
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+fn parse(input: &str) -> Result<(Vec<String>, Vec<String>), ParseError> {
     let mut lines = input.lines();
 
     let towels = lines
-        .next().unwrap()
+        .next()
+        .ok_or_else(|| ParseError::new("Missing the list of towels"))?
         .split(", ")
-        .collect::<Vec<&str>>();
+        .map(String::from)
+        .collect();
 
-    let lines = lines.skip(1);
+    let designs = lines.skip(1).map(String::from).collect();
 
-    (towels, lines)
+    Ok((towels, designs))
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let (towels, mut lines) = parse(input);
-    let mut count = 0;
-
-    // create a regex pattern
-    let any_towel_pattern: String = format!("^({})*$", towels.join("|"));
-    let reg = regex::Regex::new(&any_towel_pattern).unwrap();
-        
-    while let Some(line) = lines.next() {
-        if reg.is_match(line) {
-            count += 1;
-        }                
+fn do_it(str: &str, options: &[String], cache: &mut HashMap<String, i64>) -> i64 {
+    if let Some(&c) = cache.get(str) {
+        return c;
     }
-    Box::new(count)
-}
-
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let (towels, lines) = parse(input);
-
-    fn do_it(str: &str, options: &Vec<&str>, cache: &mut HashMap<String, i64>) -> i64 {
-        if let Some(&c) = cache.get(str) {
-            return c;
-        }
 
-        let mut count: i64 = 0;
-        for option in options {
-            if str.get(0..option.len()) == Some(option) {
-                if let Some(remaining) = str.get(option.len()..) {
-                    if remaining.is_empty() {
-                        count += 1;
-                    } else {
-                        count += do_it(remaining, options, cache);
-                    }
-                }
+    let mut count: i64 = 0;
+    for option in options {
+        if str.starts_with(option.as_str()) {
+            let remaining = &str[option.len()..];
+            if remaining.is_empty() {
+                count += 1;
             } else {
-                continue;
+                count += do_it(remaining, options, cache);
             }
         }
+    }
+
+    cache.insert(str.to_string(), count);
+    count
+}
 
-        cache.insert(str.to_string(), count);
-        count
+impl Solution for Solver {
+    type Input = (Vec<String>, Vec<String>);
+
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        parse(input)
     }
 
-    let count: i64 = lines.par_bridge().map(|line| {
-        let mut cache = HashMap::new();
-        do_it(line, &towels, &mut cache)
-    }).sum();
+    fn part1((towels, designs): &Self::Input) -> Answer {
+        // create a regex pattern
+        let any_towel_pattern: String = format!("^({})*$", towels.join("|"));
+        let reg = regex::Regex::new(&any_towel_pattern).unwrap();
 
-    Box::new(count)
-}
+        designs.iter().filter(|line| reg.is_match(line)).count().into()
+    }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+    fn part2((towels, designs): &Self::Input) -> Answer {
+        let count: i64 = designs.par_iter().map(|line| {
+            let mut cache = HashMap::new();
+            do_it(line, towels, &mut cache)
+        }).sum();
+
+        count.into()
+    }
+}
 
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         r, wr, b, g, bwu, rb, gb, br
 
         brwrr
@@ -82,7 +80,14 @@ mod tests {
         bwurrg
         brgr
         bbrgwb"
-    };
+    }).part1("6").part2("16"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
     
     const TEST_RESULT_1: i64 = 6;
     const TEST_RESULT_2: i64 = 16;
@@ -90,13 +95,13 @@ mod tests {
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&String::from(TEST_INPUT)).to_string(), TEST_RESULT_1.to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT_1.into());
     }
 
     // Test for part2
     #[test]
     fn test_part2() {
-        assert_eq!(part2(&String::from(TEST_INPUT)).to_string(), TEST_RESULT_2.to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), TEST_RESULT_2.into());
     }
     
 }
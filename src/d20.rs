@@ -1,211 +1,150 @@
-use std::collections::VecDeque;
+use indoc::indoc;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
+use crate::{error::ParseError, examples::Example, geometry::Point, grid::Grid, params::Params, search, solution::{Answer, Solution}};
+
+pub struct Solver;
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 enum Tile {
     Empty,
     Blocked
 }
 
+type Coord = Point;
+
 #[derive(Clone, PartialEq, Eq, Debug)]
-struct Grid {
-    grid: Vec<Vec<Tile>>,
-    start: (usize, usize),
-    end: (usize, usize),
-    width: usize,
-    height: usize
+pub struct Racetrack {
+    grid: Grid<Tile>,
+    start: Coord,
+    end: Coord,
+    /// The smallest saving worth counting, in picoseconds.
+    min_saving: usize,
+    /// How long a cheat may last in each part.
+    cheat_times: [i64; 2],
 }
 
 
-fn make_grid(input: &String) -> Grid {
+fn make_grid(input: &str, params: &Params) -> Result<Racetrack, ParseError> {
+    let chars = Grid::parse(input, |c| match c {
+        '#' | '.' | 'S' | 'E' => Ok(c),
+        _ => Err(ParseError::new(format!("Invalid character in input: {}", c)).expected("one of # . S E")),
+    })?;
 
-    let mut tiles = vec![];
+    let start = chars.find(&'S').ok_or_else(|| ParseError::new("No start found"))?;
+    let end = chars.find(&'E').ok_or_else(|| ParseError::new("No end found"))?;
 
-    let mut start = (0, 0);
-    let mut end = (0, 0);
-
-    for (y, line) in input.lines().enumerate() {
-        let mut row = vec![];
-        for (x, c) in line.chars().enumerate() {
-            match c {
-                '#' => row.push(Tile::Blocked),
-                '.' => row.push(Tile::Empty),
-                'S' => {
-                    row.push(Tile::Empty);
-                    start = (x, y);
-                }
-                'E' => {
-                    row.push(Tile::Empty);
-                    end = (x, y);
-                }
-                _ => panic!("Invalid character in input")
-            }
-        }
-        tiles.push(row);
-    }
-
-    Grid {
-        width: tiles[0].len(),
-        height: tiles.len(),
-        grid: tiles,
+    Ok(Racetrack {
+        grid: chars.map(|&c| if c == '#' { Tile::Blocked } else { Tile::Empty }),
         start,
-        end
-    }
+        end,
+        min_saving: params.get("min_saving")?,
+        cheat_times: [params.get("part1_cheat")?, params.get("part2_cheat")?],
+    })
 }
 
-fn find_path(grid: &Grid) -> Option<Vec<(usize, usize)>> {
-    let width = grid.width;
-    let height = grid.height;
+fn find_path(track: &Racetrack) -> Option<Vec<Coord>> {
+    let grid = &track.grid;
 
-    let start = grid.start;
-    let end = grid.end;
+    let found = search::bfs(
+        track.start,
+        |&p| grid.neighbours4(p).filter(|&n| grid.get(n) == Some(&Tile::Empty)).collect::<Vec<_>>(),
+        |&p| p == track.end,
+    )?;
 
-    let mut queue = VecDeque::new();
-    queue.push_back(start);
-
-    let mut costs = vec![vec![std::i64::MAX; width]; height];
-    costs[start.1][start.0] = 0;
-    
-    while let Some((x, y)) = queue.pop_front() {
-        let cost = costs[y][x];
-        if (x, y) == end {
-            break;
-        }
-
-        for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, -1)].iter() {
-            let nx = x as i64 + dx;
-            let ny = y as i64 + dy;
+    Some(found.path)
+}
 
-            if nx >= 0 && nx < width as i64 && ny >= 0 && ny < height as i64 {
-                let nx = nx as usize;
-                let ny = ny as usize;
+/// The cheats of up to `cheat_time` saving enough, `None` when the track has no way to the end.
+fn count_around_kernel(track: &Racetrack, cheat_time: i64) -> Option<usize> {
+    let min_saving = track.min_saving;
+    let path = find_path(track)?;
 
-                if grid.grid[ny][nx] == Tile::Empty && cost + 1 < costs[ny][nx] {
-                    costs[ny][nx] = cost + 1;
-                    queue.push_back((nx, ny));
-                }
-            }
-        }
-    }
-
-    if costs[end.1][end.0] == std::i64::MAX {
-        return None;
+    // index the path
+    let mut path_grid = Grid::new(track.grid.width(), track.grid.height(), None);
+    for (i, &p) in path.iter().enumerate() {
+        path_grid.set(p, Some(i));
     }
 
-    let mut path = vec![];
-    let mut current = end;
-
-    while current != start {
-        path.push(current);
-        let cost = costs[current.1][current.0];
-        for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, -1)].iter() {
-            let nx = current.0 as i64 + dx;
-            let ny = current.1 as i64 + dy;
-
-            if nx >= 0 && nx < width as i64 && ny >= 0 && ny < height as i64 {
-                let nx = nx as usize;
-                let ny = ny as usize;
+    let count = path.par_iter().enumerate().map(|(i, &p)| {
+        let mut count = 0;
+        
+        for n in p.within_manhattan(cheat_time) {
+            if let Some(&Some(j)) = path_grid.get(n) {
+                let j = j as i64;
+                let i = i as i64;
 
-                if grid.grid[ny][nx] == Tile::Empty && costs[ny][nx] == cost - 1 {
-                    current = (nx, ny);
-                    break;
+                if j - i - p.manhattan(n) < min_saving as i64 {
+                    continue;
                 }
+
+                count += 1;
             }
         }
-    }
 
-    path.push(start);
+        count
+    }).sum();
 
-    path.reverse();
-    Some(path)
+    Some(count)
 }
 
-fn count_around_kernel(input: &String, min_saving: usize, cheat_time: i64) -> usize {
-    
-    let map = make_grid(input);
 
-    let path = find_path(&map).unwrap();
+impl Solution for Solver {
+    type Input = Racetrack;
 
-    let h = map.width as i64;
-    let w = map.height as i64;
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("min_saving", "100"), ("part1_cheat", "2"), ("part2_cheat", "20")];
 
-    // index the path
-    let mut path_grid = vec![vec![None; h as usize]; w as usize];
-    for (i, &(x, y)) in path.iter().enumerate() {
-        path_grid[x][y] = Some(i);
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        make_grid(input, params)
     }
 
-    path.par_iter().enumerate().map(|(i, &(x, y))| {
-        let x = x as i64;
-        let y = y as i64;
-        let mut count = 0;
-        
-        for dx  in -cheat_time..=cheat_time {
-            let r = cheat_time - dx.abs();
-
-            for dy in -r..=r {
-                let nx = x + dx;
-                let ny = y + dy;
-                
-                if let Some(&Some(j)) = path_grid.get(nx as usize).and_then(|row| row.get(ny as usize)) {
-                    let j = j as i64;
-                    let i = i as i64;
-
-                    if j - i - dx.abs() - dy.abs() < min_saving as i64 {
-                        continue;
-                    }
-
-                    count += 1;
-                }
-            }
-        }
-
-        count
-    }).sum()
-}
-
+    fn part1(track: &Self::Input) -> Answer {
+        count_around_kernel(track, track.cheat_times[0]).map_or(Answer::Unsolved, Answer::from)
+    }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    Box::new(count_around_kernel(input, 100, 2))
+    fn part2(track: &Self::Input) -> Answer {
+        count_around_kernel(track, track.cheat_times[1]).map_or(Answer::Unsolved, Answer::from)
+    }
 }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    Box::new(count_around_kernel(input, 100, 20))
-}
+const EXAMPLE: &str = indoc! {"
+    ###############
+    #...#...#.....#
+    #.#.#.#.#.###.#
+    #S#...#.#.#...#
+    #######.#.#.###
+    #######.#.#...#
+    #######.#.###.#
+    ###..E#...#...#
+    ###.#######.###
+    #...###...#...#
+    #.#####.#.###.#
+    #.#...#.#.#...#
+    #.#.#.#.#.#.###
+    #...#...#...###
+    ###############"
+};
+
+// the example track is too short to save 100 picoseconds, so it counts smaller savings
+pub const EXAMPLES: &[Example] = &[
+    Example::new(EXAMPLE).part1("44").params(&[("min_saving", "2")]),
+    Example::new(EXAMPLE).part2("285").params(&[("min_saving", "50")]),
+];
 
 #[cfg(test)]
 mod tests {
-    use indoc::indoc;
     use super::*;
 
-    const TEST_INPUT: &str = indoc! {"
-        ###############
-        #...#...#.....#
-        #.#.#.#.#.###.#
-        #S#...#.#.#...#
-        #######.#.#.###
-        #######.#.#...#
-        #######.#.###.#
-        ###..E#...#...#
-        ###.#######.###
-        #...###...#...#
-        #.#####.#.###.#
-        #.#...#.#.#...#
-        #.#.#.#.#.#.###
-        #...#...#...###
-        ###############"
-    };
-
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(count_around_kernel(&TEST_INPUT.to_string(), 2, 2), 44);
+        assert_eq!(Solver::part1(&EXAMPLES[0].parse::<Solver>().unwrap()), 44.into());
     }
 
     // Test for part2
     #[test]
     fn test_part2() {
-        assert_eq!(count_around_kernel(&TEST_INPUT.to_string(), 50, 20), 285);
+        assert_eq!(Solver::part2(&EXAMPLES[1].parse::<Solver>().unwrap()), 285.into());
     }
     
     
@@ -1,42 +1,22 @@
-use std::{collections::HashMap, sync::LazyLock, vec};
+use std::{collections::HashMap, fmt::{self, Display}, sync::LazyLock};
 
+use indoc::indoc;
+
+use crate::{artifacts::Kind, context::Context, error::ParseError, examples::Example, geometry::{Direction, Point}, grid::Grid, params::Params, search, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+#[derive(Debug)]
 enum Button {
     Empty, 
     Button(char)
 }
 
-impl ToString for Button {
-    fn to_string(&self) -> String {
-        match self {
-            Button::Empty => "".to_string(),
-            Button::Button(c) => c.to_string()
-        }
-    }
-}
-
-#[derive(Clone, Copy, PartialEq, Eq, Debug)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right
-}
-
-impl Direction {
-    fn to_char(&self) -> char {
+impl Display for Button {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Direction::Up => '^',
-            Direction::Down => 'v',
-            Direction::Left => '<',
-            Direction::Right => '>',
-        }
-    }
-    fn to_dir(&self) -> (isize, isize) {
-        match self {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
+            Button::Empty => Ok(()),
+            Button::Button(c) => write!(f, "{}", c)
         }
     }
 }
@@ -49,216 +29,380 @@ enum Action {
 impl Action {
     fn to_char(&self) -> char {
         match self {
-            Action::Move(d) => d.to_char(),
+            Action::Move(d) => char::from(*d),
             Action::Push => 'A',
         }
     }
 }
 
-static NUMPAD: LazyLock<Vec<Vec<Button>>> = LazyLock::new(||vec![
-    vec![Button::Button('7'), Button::Button('8'), Button::Button('9')],
-    vec![Button::Button('4'), Button::Button('5'), Button::Button('6')],
-    vec![Button::Button('1'), Button::Button('2'), Button::Button('3')],
-    vec![Button::Empty,       Button::Button('0'), Button::Button('A')]
-]);
+/// A keypad laid out like its picture: a button per character, with spaces for the gaps. The
+/// buttons and their positions come from the same picture, so they always agree.
+#[derive(Debug)]
+pub struct Keypad {
+    buttons: Grid<Button>,
+    index: HashMap<char, Point>,
+}
 
+impl Keypad {
+    /// Reads a keypad from its picture, rows may be left short and are filled up with gaps. Blank
+    /// lines around the picture are left out, a blank line inside it is a row of gaps.
+    /// Every robot arm starts on A, so the keypad needs one and every button has to be reachable from it.
+    pub fn new(picture: &str) -> Result<Keypad, ParseError> {
+        let lines: Vec<&str> = picture.lines().collect();
+        let first = lines.iter().position(|row| !row.trim().is_empty()).unwrap_or(lines.len());
+        let last = lines.iter().rposition(|row| !row.trim().is_empty()).map_or(first, |last| last + 1);
+        let rows = &lines[first..last];
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+
+        let mut cells = Vec::new();
+        let mut index = HashMap::new();
+        for (y, row) in rows.iter().enumerate() {
+            let mut chars = row.char_indices();
+            for x in 0..width {
+                match chars.next() {
+                    None | Some((_, ' ')) => cells.push(Button::Empty),
+                    Some((i, c)) => {
+                        if index.insert(c, Point::new(x as i64, y as i64)).is_some() {
+                            return Err(ParseError::new(format!("The button {} is on the keypad twice", c)).at(picture, &row[i..i + c.len_utf8()]));
+                        }
+                        cells.push(Button::Button(c));
+                    }
+                }
+            }
+        }
 
-static DIRPAD: LazyLock<Vec<Vec<Button>>> = LazyLock::new(||vec![
-    vec![Button::Empty,       Button::Button('^'), Button::Button('A')],
-    vec![Button::Button('<'), Button::Button('v'), Button::Button('>')],
-]);
+        let Some(&start) = index.get(&'A') else {
+            return Err(ParseError::new("The keypad has no A button").expected("an A button, where the arm starts"));
+        };
 
-static NUMPAD_INDEX: LazyLock<HashMap<char, (usize, usize)>> = LazyLock::new(|| index_pad(&*NUMPAD));
-static DIRPAD_INDEX: LazyLock<HashMap<char, (usize, usize)>> = LazyLock::new(|| index_pad(&*DIRPAD));
+        let keypad = Keypad { buttons: Grid::from_vec(width, rows.len(), cells), index };
+        let reachable = search::bfs_distances(start, |&pos| keypad.steps(pos).map(|(_, next)| next).collect::<Vec<_>>());
+        if let Some((&button, _)) = keypad.index.iter().filter(|&(_, pos)| !reachable.contains_key(pos)).min() {
+            let i = picture.find(button).unwrap();
+            return Err(ParseError::new(format!("The button {} cannot be reached from A", button)).at(picture, &picture[i..i + button.len_utf8()]));
+        }
 
-fn index_pad(buttons: &Vec<Vec<Button>>) -> HashMap<char, (usize, usize)> {
-    let mut map = HashMap::new();
-    for (y, row) in buttons.iter().enumerate() {
-        for (x, button) in row.iter().enumerate() {
-            if let Button::Button(c) = button {
-                map.insert(*c, (x, y));
-            }
+        Ok(keypad)
+    }
+
+    /// Reads a directional keypad, which needs a button for each direction besides A.
+    pub fn directional(picture: &str) -> Result<Keypad, ParseError> {
+        let keypad = Keypad::new(picture)?;
+
+        match ['^', 'v', '<', '>'].into_iter().find(|button| !keypad.index.contains_key(button)) {
+            Some(button) => Err(ParseError::new(format!("The directional keypad has no {} button", button)).expected("the buttons ^, v, <, > and A")),
+            None => Ok(keypad),
         }
     }
-    map
-}
 
-struct Robot {
-    directions: usize,
-    pad: &'static Vec<Vec<Button>>,
-    index: &'static HashMap<char, (usize, usize)>, 
-    x: usize,
-    y: usize
-}
+    fn position(&self, button: char) -> Option<Point> {
+        self.index.get(&button).copied()
+    }
 
-static DIRECTIONS: LazyLock<Vec<Vec<Direction>>> = LazyLock::new(|| 
-    // all 24 orders of directions
-    vec![
-        vec![Direction::Up, Direction::Down, Direction::Left, Direction::Right],
-        vec![Direction::Up, Direction::Down, Direction::Right, Direction::Left],
-        vec![Direction::Up, Direction::Left, Direction::Down, Direction::Right],
-        vec![Direction::Up, Direction::Left, Direction::Right, Direction::Down],
-        vec![Direction::Up, Direction::Right, Direction::Down, Direction::Left],
-        vec![Direction::Up, Direction::Right, Direction::Left, Direction::Down],
-        vec![Direction::Down, Direction::Up, Direction::Left, Direction::Right],
-        vec![Direction::Down, Direction::Up, Direction::Right, Direction::Left],
-        vec![Direction::Down, Direction::Left, Direction::Up, Direction::Right],
-        vec![Direction::Down, Direction::Left, Direction::Right, Direction::Up],
-        vec![Direction::Down, Direction::Right, Direction::Up, Direction::Left],
-        vec![Direction::Down, Direction::Right, Direction::Left, Direction::Up],
-        vec![Direction::Left, Direction::Up, Direction::Down, Direction::Right],
-        vec![Direction::Left, Direction::Up, Direction::Right, Direction::Down],
-        vec![Direction::Left, Direction::Down, Direction::Up, Direction::Right],
-        vec![Direction::Left, Direction::Down, Direction::Right, Direction::Up],
-        vec![Direction::Left, Direction::Right, Direction::Up, Direction::Down],
-        vec![Direction::Left, Direction::Right, Direction::Down, Direction::Up],
-        vec![Direction::Right, Direction::Up, Direction::Down, Direction::Left],
-        vec![Direction::Right, Direction::Up, Direction::Left, Direction::Down],
-        vec![Direction::Right, Direction::Down, Direction::Up, Direction::Left],
-        vec![Direction::Right, Direction::Down, Direction::Left, Direction::Up],
-        vec![Direction::Right, Direction::Left, Direction::Up, Direction::Down],
-        vec![Direction::Right, Direction::Left, Direction::Down, Direction::Up],
-    ]
-);
-
-fn get_best_transition(from: char, to: char) -> usize {
-    if from == to {
-        return 1;
+    /// The moves from `pos` that keep the arm on a button, with where they lead.
+    fn steps(&self, pos: Point) -> impl Iterator<Item = (Direction, Point)> + '_ {
+        Direction::ALL.into_iter()
+            .map(move |dir| (dir, pos + dir.vector()))
+            .filter(|&(_, next)| matches!(self.buttons.get(next), Some(Button::Button(_))))
     }
 
-    let mut best_length = usize::MAX;
-    
-    for directions_1 in 0..DIRECTIONS.len() {
-        for directions_2 in 0..DIRECTIONS.len() {
-            let mut robots = vec![Robot::new_numpad(directions_1), Robot::new_dirpad(directions_2), Robot::new_dirpad(0)];
-            let &(x,y) = NUMPAD_INDEX.get(&from).unwrap();
-            robots[0].x = x;
-            robots[0].y = y;
-
-            let sequence: String= robots.iter_mut().fold(to.to_string(), |acc, robot| {
-                let seq: String = robot.find_sequence(&acc).iter().map(|a| a.to_char()).collect();
-                seq
-            });
-
-            if sequence.len() < best_length {
-                best_length = sequence.len();
-            }
+    /// The ways to move from `from` to `to` and push it, every shortest one that never points the
+    /// arm at a gap. Empty when either button is not on the keypad.
+    fn paths(&self, from: char, to: char) -> Vec<Vec<Action>> {
+        let (Some(start), Some(end)) = (self.position(from), self.position(to)) else {
+            return Vec::new();
+        };
+
+        // a shortest way only takes steps getting one closer to the end
+        let distances = search::bfs_distances(end, |&pos| self.steps(pos).map(|(_, next)| next).collect::<Vec<_>>());
+        let mut paths = vec![(start, Vec::new())];
+        for distance in (0..distances[&start]).rev() {
+            paths = paths.into_iter().flat_map(|(pos, path)| {
+                self.steps(pos)
+                    .filter(|(_, next)| distances.get(next) == Some(&distance))
+                    .map(move |(dir, next)| (next, [path.clone(), vec![dir]].concat()))
+            }).collect();
         }
-    }
 
-    best_length
-}
+        paths.into_iter()
+            .map(|(_, path)| path.into_iter().map(Action::Move).chain([Action::Push]).collect())
+            .collect()
+    }
 
-impl Robot {
-    fn new(pad: &'static Vec<Vec<Button>>, index: &'static HashMap<char, (usize, usize)>, directions: usize) -> Robot {
-        let &(x,y) = index.get(&'A').unwrap();
+    /// What a robot on this keypad types when given `presses`, or `None` when they point its arm at a gap.
+    #[cfg(test)]
+    fn type_presses(&self, presses: &str) -> Option<String> {
+        let mut pos = self.position('A')?;
+        let mut typed = String::new();
 
-        Robot {
-            pad,
-            directions,
-            index,
-            x,
-            y
+        for c in presses.chars() {
+            if c != 'A' {
+                pos += Direction::try_from(c).ok()?.vector();
+            }
+            match (c, self.buttons.get(pos)?) {
+                (_, Button::Empty) => return None,
+                ('A', Button::Button(button)) => typed.push(*button),
+                _ => {}
+            }
         }
-    }
 
-    fn new_numpad(directions: usize) -> Robot {
-        Robot::new(&*NUMPAD, &*NUMPAD_INDEX, directions)
+        Some(typed)
     }
+}
 
-    fn new_dirpad(directions: usize) -> Robot {
-        Robot::new(&*NUMPAD, &*DIRPAD_INDEX, directions)
+static NUMPAD: LazyLock<Keypad> = LazyLock::new(|| Keypad::new(indoc! {"
+    789
+    456
+    123
+     0A"
+}).unwrap());
+
+static DIRPAD: LazyLock<Keypad> = LazyLock::new(|| Keypad::directional(indoc! {"
+     ^A
+    <v>"
+}).unwrap());
+
+/// Button presses, memoised by the buttons and the number of directional keypads still above.
+/// A cache only holds the costs for one directional keypad.
+type Cache = HashMap<(char, char, usize), usize>;
+
+/// The fewest presses on the topmost keypad to make the robot `depth` keypads below it move from
+/// `from` to `to` on the directional keypad `dirpad` and push it. Every robot above starts and ends
+/// on A, so the cost of a move does not depend on what was pressed before. `None` when the move
+/// cannot be made.
+fn cost(dirpad: &Keypad, from: char, to: char, depth: usize, cache: &mut Cache) -> Option<usize> {
+    if depth == 0 {
+        return Some(1);
+    }
+    if let Some(&cost) = cache.get(&(from, to, depth)) {
+        return Some(cost);
     }
 
-    fn find_sequence(&mut self, input: &String) -> Vec<Action> {
-        let mut sequence = vec![];
+    let best = dirpad.paths(from, to).iter()
+        .filter_map(|path| sequence_cost(dirpad, path, depth - 1, cache))
+        .min()?;
 
-        for c in input.chars() {
-            let &(tx, ty) = self.index.get(&c).unwrap();
-            
-            loop {
-                // find first direction
-                let dx = (tx as isize - self.x as isize).signum();
-                let dy = (ty as isize - self.y as isize).signum();
+    cache.insert((from, to, depth), best);
+    Some(best)
+}
 
-                if dx == 0 && dy == 0 {
-                    break;
-                }
+/// The fewest presses on the topmost keypad to type `actions` on the directional keypad `dirpad`
+/// `depth` keypads below.
+fn sequence_cost(dirpad: &Keypad, actions: &[Action], depth: usize, cache: &mut Cache) -> Option<usize> {
+    let mut previous = 'A';
+
+    actions.iter().map(|action| {
+        let c = action.to_char();
+        let cost = cost(dirpad, previous, c, depth, cache);
+        previous = c;
+        cost
+    }).sum()
+}
 
-                let &dir = DIRECTIONS[self.directions].iter().find(|&d| {
-                    let (dx_, dy_) = d.to_dir();
-                    // where would I end up if I moved in this direction
-                    if let Some(Button::Empty) = self.pad.get((self.y as isize + dy_) as usize).and_then(|row| row.get((self.x as isize + dx_) as usize)) {
-                        return false;
-                    }
-                    dx != 0 && dx_ == dx || dy != 0 && dy_ == dy
-                }).expect(format!("No direction found for movement {},{}", dx,dy).as_str());
-                
-                let (dx, dy) = dir.to_dir();
-                self.x = (self.x as isize + dx) as usize;
-                self.y = (self.y as isize + dy) as usize;
-
-                sequence.push(Action::Move(dir));
-            
-            }
+/// The fewest presses to type `code` on `pad` through `robots` directional keypads like `dirpad`,
+/// `None` when the code cannot be typed.
+fn code_cost(pad: &Keypad, dirpad: &Keypad, code: &str, robots: usize, cache: &mut Cache) -> Option<usize> {
+    let mut previous = 'A';
+
+    code.chars().map(|c| {
+        let best = pad.paths(previous, c).iter()
+            .filter_map(|path| sequence_cost(dirpad, path, robots, cache))
+            .min();
+        previous = c;
+        best
+    }).sum()
+}
 
-            sequence.push(Action::Push);
+/// One of the shortest sequences of presses on the topmost keypad typing `code` on `pad` through
+/// `robots` directional keypads like `dirpad`. It grows exponentially with `robots`, only the cost
+/// scales to long chains. `None` when the code cannot be typed.
+fn sequence(pad: &Keypad, dirpad: &Keypad, code: &str, robots: usize, cache: &mut Cache) -> Option<String> {
+    let mut previous = 'A';
+    let mut presses = String::new();
+
+    for c in code.chars() {
+        let paths = pad.paths(previous, c);
+        let (_, best) = paths.iter().filter_map(|path| Some((sequence_cost(dirpad, path, robots, cache)?, path))).min_by_key(|&(cost, _)| cost)?;
+        let best: String = best.iter().map(Action::to_char).collect();
+
+        match robots {
+            0 => presses += &best,
+            _ => presses += &sequence(dirpad, dirpad, &best, robots - 1, cache)?,
         }
+        previous = c;
+    }
 
-        sequence
-        
+    Some(presses)
+}
+
+/// The longest robot chain whose sequences get shown.
+const MAX_SHOWN_ROBOTS: usize = 3;
+
+/// The sum of each code's length times its number, showing the presses typing each code and
+/// keeping them as the `sequences` artifact. `None` when a code cannot be typed.
+fn complexity(codes: &[String], robots: usize, context: &Context) -> Option<usize> {
+    let mut cache = Cache::new();
+    let mut shown = String::new();
+
+    let sum = codes.iter().map(|code| {
+        // the sequences get too long to show past a few robots
+        if robots <= MAX_SHOWN_ROBOTS {
+            let line = format!("{}: {}", code, sequence(&NUMPAD, &DIRPAD, code, robots, &mut cache)?);
+            context.print(&line);
+            shown += &line;
+            shown.push('\n');
+        }
+
+        Some(code_cost(&NUMPAD, &DIRPAD, code, robots, &mut cache)? * code[0..3].parse::<usize>().unwrap())
+    }).sum();
+
+    if !shown.is_empty() {
+        context.emit("sequences", Kind::Text, shown);
     }
+
+    sum
 }
 
+/// The door codes and the number of robots on directional keypads in each part.
+pub struct Codes {
+    codes: Vec<String>,
+    robots: [usize; 2],
+}
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
+impl Solution for Solver {
+    type Input = Codes;
 
-    let mut sum = 0;
-    let mut cache = HashMap::new(); 
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("part1_robots", "2"), ("part2_robots", "25")];
 
-    for line in input.lines() {
-        let mut len = 0;
-        let mut previous = 'A';
-        for c in line.chars() {
-            // parse as hex number
-            let seqlen = cache.entry((previous, c))
-                .or_insert_with(|| get_best_transition(previous, c))
-                .clone();
-            len += seqlen;
-            previous = c;
-        }
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        let codes = input.lines().map(|line| {
+            // a code is three digits followed by the A key, like 029A
+            if let Some((i, c)) = line.char_indices().find(|&(i, c)| !matches!((i, c), (0..3, '0'..='9') | (3, 'A'))) {
+                let expected = if i < 3 { "a digit" } else if i == 3 { "A" } else { "the end of the code" };
+                return Err(ParseError::new(format!("Invalid character {} in code", c)).at(input, &line[i..i + c.len_utf8()]).expected(expected));
+            }
+            if line.len() != 4 {
+                return Err(ParseError::new("The code is too short").at(input, line).expected("a code like 029A"));
+            }
 
-        let num = line[0..3].chars().collect::<String>().parse::<usize>().unwrap();
-        //println!("{} * {}", sequence.len(), num);
-        sum += len * num;
+            Ok(line.to_string())
+        }).collect::<Result<_, _>>()?;
+
+        Ok(Codes { codes, robots: [params.get("part1_robots")?, params.get("part2_robots")?] })
     }
 
-    Box::new(sum)
-}
+    fn part1(input: &Self::Input) -> Answer {
+        Self::part1_with(input, &Context::headless())
+    }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    Box::new(0)
-}
+    fn part2(input: &Self::Input) -> Answer {
+        Self::part2_with(input, &Context::headless())
+    }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+    fn part1_with(input: &Self::Input, context: &Context) -> Answer {
+        complexity(&input.codes, input.robots[0], context).map_or(Answer::Unsolved, Answer::from)
+    }
 
-    const TEST_INPUT: &str = indoc! {"
+    fn part2_with(input: &Self::Input, context: &Context) -> Answer {
+        complexity(&input.codes, input.robots[1], context).map_or(Answer::Unsolved, Answer::from)
+    }
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         029A
         980A
         179A
         456A
         379A"
-    };
+    }).part1("126384").part2("154115708116294"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{Output, Verbosity};
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
 
     // Test for part1
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&TEST_INPUT.to_string()).to_string(), "126384".to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), 126384.into());
     }
 
-    
-    
+    // Test for part2
+    #[test]
+    fn test_part2() {
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), 154115708116294usize.into());
+    }
+
+    #[test]
+    fn test_shown_sequences() {
+        let context = Context::new(Output::Captured(String::new()), None, Verbosity::Normal, true);
+        Solver::part1_with(&Solver::parse(TEST_INPUT).unwrap(), &context);
+
+        let shown = context.captured();
+        assert_eq!(shown.lines().count(), 5);
+        assert!(shown.starts_with("029A: "));
+        assert_eq!(shown.lines().next().unwrap().len(), "029A: ".len() + 68);
+    }
+
+    #[test]
+    fn test_code_cost() {
+        // the lengths of <A^A>^^AvvvA and the sequences typing it from one and two keypads up
+        let mut cache = Cache::new();
+        assert_eq!(code_cost(&NUMPAD, &DIRPAD, "029A", 0, &mut cache), Some(12));
+        assert_eq!(code_cost(&NUMPAD, &DIRPAD, "029A", 1, &mut cache), Some(28));
+        assert_eq!(code_cost(&NUMPAD, &DIRPAD, "029A", 2, &mut cache), Some(68));
+        assert_eq!(code_cost(&NUMPAD, &DIRPAD, "02BA", 2, &mut cache), None);
+    }
+
+    #[test]
+    fn test_keypad() {
+        assert_eq!(DIRPAD.position('<'), Some(Point::new(0, 1)));
+        assert_eq!(DIRPAD.type_presses("<A<A"), None);
+        assert_eq!(DIRPAD.type_presses("v<<A"), Some("<".to_string()));
+        assert!(Keypad::new("12\n1A").unwrap_err().to_string().contains("twice"));
+        assert!(Keypad::new("12\n34").unwrap_err().to_string().contains("no A"));
+        assert!(Keypad::directional("<^A\n  >").unwrap_err().to_string().contains("no v"));
+
+        // a short row gets gaps at its end
+        let pad = Keypad::new("BA\nC").unwrap();
+        assert_eq!(pad.paths('C', 'A').len(), 1);
+
+        // the blank lines around are not rows, a blank row inside is a row of gaps cutting the keypad in two
+        let pad = Keypad::new("\n\nBA\nC\n\n").unwrap();
+        assert_eq!(pad.position('C'), Some(Point::new(0, 1)));
+        let error = Keypad::new("\nBA\n  \nCD\n\n").unwrap_err();
+        assert!(error.to_string().contains("The button C cannot be reached from A"));
+
+        // going around a gap takes more moves than the distance between the buttons
+        let pad = Keypad::new("A B\nCDE").unwrap();
+        let paths: Vec<String> = pad.paths('A', 'B').iter().map(|path| path.iter().map(Action::to_char).collect()).collect();
+        assert_eq!(paths, ["v>>^A"]);
+        assert_eq!(pad.paths('D', 'B').len(), 1);
+        assert_eq!(Keypad::new("AB\nCD").unwrap().paths('A', 'D').len(), 2);
+    }
+
+    #[test]
+    fn test_sequence() {
+        let pad = Keypad::new(" AB\nCDE").unwrap();
+        let gap = Keypad::new("A B\nCDE").unwrap();
+        let flipped = Keypad::directional("<v>\n ^A").unwrap();
+
+        for dirpad in [&*DIRPAD, &flipped] {
+            let mut cache = Cache::new();
+            for (pad, code) in [(&*NUMPAD, "029A"), (&*NUMPAD, "379A"), (&pad, "CEBDA"), (&gap, "BCEA")] {
+                for robots in 0..3 {
+                    let presses = sequence(pad, dirpad, code, robots, &mut cache).unwrap();
+                    assert_eq!(Some(presses.len()), code_cost(pad, dirpad, code, robots, &mut cache));
+
+                    // typing the presses back down the chain gives the code again
+                    let typed = (0..robots).try_fold(presses, |presses, _| dirpad.type_presses(&presses));
+                    assert_eq!(typed.and_then(|typed| pad.type_presses(&typed)).as_deref(), Some(code));
+                }
+            }
+        }
+    }
 }
\ No newline at end of file
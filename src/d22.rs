@@ -1,7 +1,13 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use indoc::indoc;
 use itertools::Itertools;
 use rayon::prelude::*;
+use tracing::trace;
+
+use crate::{error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
 
 fn mix(num: u64, mix: u64) -> u64 {
     num ^ mix
@@ -80,17 +86,7 @@ fn run2000(num: u64) -> u64 {
     last
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    Box::new(
-        input.lines()
-            .par_bridge()
-            .map(|line| line.parse().unwrap())
-            .map(run2000)
-            .sum::<u64>()
-    )
-}
-
-fn has_sequence_encoded(sequences: &Vec<u32>, digits: &Vec<u8>, combination: u32) -> Option<u8> {
+fn has_sequence_encoded(sequences: &[u32], digits: &[u8], combination: u32) -> Option<u8> {
     for (i, &seq) in sequences.iter().enumerate() {
         if seq == combination {
             return Some(digits[i]);
@@ -100,7 +96,7 @@ fn has_sequence_encoded(sequences: &Vec<u32>, digits: &Vec<u8>, combination: u32
     None
 }
 
-fn run_partial_encoded(nums: &Vec<(Vec<u32>, Vec<u8>)>, n1: i8) -> u64 {
+fn run_partial_encoded(nums: &[(Vec<u32>, Vec<u8>)], n1: i8) -> u64 {
     let mut best_score: u64 = 0;
 
     for n2 in -9..=9 {
@@ -109,7 +105,7 @@ fn run_partial_encoded(nums: &Vec<(Vec<u32>, Vec<u8>)>, n1: i8) -> u64 {
                 let combination = encode((n1, n2, n3, n4));
                 let s = nums.iter().filter_map(|num| has_sequence_encoded(&num.0, &num.1, combination)).map(|u8| u8 as u64).sum();
                 if s > best_score {
-                    println!("{:?}: {}", decode(combination), s);
+                    trace!(sequence = ?decode(combination), bananas = s, "better sequence");
                     best_score = s;
                 }
             }
@@ -119,37 +115,60 @@ fn run_partial_encoded(nums: &Vec<(Vec<u32>, Vec<u8>)>, n1: i8) -> u64 {
     best_score
 }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let nums: Vec<(Vec<u32>, Vec<u8>)> = input.lines().map(|line| encode_sequences(line.parse().unwrap())).collect();
+impl Solution for Solver {
+    type Input = Vec<u64>;
 
-    // mutex for best_score
-    let best_score = AtomicU64::new(0);
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        input
+            .lines()
+            .map(|line| line.parse().map_err(|_| ParseError::new(format!("{} is not a number", line)).at(input, line)))
+            .collect()
+    }
 
-    (-9..=9).par_bridge().for_each(|n1| {
-        let best = run_partial_encoded(&nums, n1);
-        best_score.fetch_max(best, Ordering::Relaxed);
-    });
+    fn part1(nums: &Self::Input) -> Answer {
+        nums
+            .par_iter()
+            .map(|&num| run2000(num))
+            .sum::<u64>()
+            .into()
+    }
 
-    Box::new(best_score.load(Ordering::Relaxed))
-}
+    fn part2(nums: &Self::Input) -> Answer {
+        let nums: Vec<(Vec<u32>, Vec<u8>)> = nums.iter().map(|&num| encode_sequences(num)).collect();
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+        // mutex for best_score
+        let best_score = AtomicU64::new(0);
+
+        (-9..=9).par_bridge().for_each(|n1| {
+            let best = run_partial_encoded(&nums, n1);
+            best_score.fetch_max(best, Ordering::Relaxed);
+        });
+
+        best_score.load(Ordering::Relaxed).into()
+    }
+}
 
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         1
         10
         100
         2024"
-    };
-    const TEST_INPUT_2: &str = indoc! {"
+    }).part1("37327623"),
+    Example::new(indoc! {"
         1
         2
         3
         2024"
-    };
+    }).part2("23"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
+    const TEST_INPUT_2: &str = EXAMPLES[1].input;
 
 
     #[test]
@@ -196,7 +215,7 @@ mod tests {
     
     #[test]
     fn test_part1() {
-        assert_eq!(part1(&TEST_INPUT.to_string()).to_string(), "37327623".to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), 37327623.into());
     }
 
     #[test]
@@ -232,6 +251,6 @@ mod tests {
 
     #[test]
     fn test_part2_encoded() {
-        assert_eq!(part2(&TEST_INPUT_2.to_string()).to_string(), "23".to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT_2).unwrap()), 23.into());
     }
 }
\ No newline at end of file
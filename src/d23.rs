@@ -1,41 +1,50 @@
 use std::collections::{HashMap, HashSet};
 
-fn parse_input(input: &String) -> Vec<(&str, &str)> {
-    input.lines().map(|l| l.split_once('-').unwrap()).collect()
+use indoc::indoc;
+
+use crate::{artifacts::Kind, context::Context, error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+type Link = (String, String);
+
+fn parse_input(input: &str) -> Result<Vec<Link>, ParseError> {
+    input.lines().map(|l| {
+        l.split_once('-')
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .ok_or_else(|| ParseError::new("Missing '-'").at(input, l).expected("a link like ab-cd"))
+    }).collect()
 }
 
-fn create_graph<'a>(links: &'a Vec<(&str, &str)>) -> HashMap<&'a str, HashSet<&'a str>> {
-    let mut graph = HashMap::new();
+fn create_graph(links: &[Link]) -> HashMap<&str, HashSet<&str>> {
+    let mut graph: HashMap<&str, HashSet<&str>> = HashMap::new();
 
-    for &(l, r) in links {
-        graph.entry(l).or_insert(HashSet::new()).insert(r);
-        graph.entry(r).or_insert(HashSet::new()).insert(l);
+    for (l, r) in links {
+        graph.entry(l).or_default().insert(r);
+        graph.entry(r).or_default().insert(l);
     }
 
     graph
 }
 
-fn create_adjacency_matrix<'a>(links: &'a Vec<(&str, &str)>) -> (Vec<&'a str>, Vec<Vec<bool>>) {
+fn create_adjacency_matrix(links: &[Link]) -> (Vec<&str>, Vec<Vec<bool>>) {
     let mut counter = 0;
     let mut nodes = HashMap::new();
     let mut index = Vec::new();
-    for &(l, r) in links {
-        if !nodes.contains_key(l) {
-            nodes.insert(l, counter);
-            index.push(l);
-            counter += 1;
-        }
-        if !nodes.contains_key(r) {
-            nodes.insert(r, counter);
-            index.push(r);
-            counter += 1;
+    for (l, r) in links {
+        for node in [l.as_str(), r.as_str()] {
+            if !nodes.contains_key(node) {
+                nodes.insert(node, counter);
+                index.push(node);
+                counter += 1;
+            }
         }
     }
 
     let mut matrix = vec![vec![false; counter]; counter];
-    for &(l, r) in links {
-        let l = nodes[l];
-        let r = nodes[r];
+    for (l, r) in links {
+        let l = nodes[l.as_str()];
+        let r = nodes[r.as_str()];
         matrix[l][r] = true;
         matrix[r][l] = true;
     }
@@ -44,31 +53,6 @@ fn create_adjacency_matrix<'a>(links: &'a Vec<(&str, &str)>) -> (Vec<&'a str>, V
 
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let links = parse_input(input);
-    let (index, matrix) = create_adjacency_matrix(&links);
-
-    // let mut nets = Vec::new();
-    let mut count = 0;
-
-    for i in 0..index.len() {
-        for j in i+1..index.len() {
-            if matrix[i][j] {
-                for k in j+1..index.len() {
-                    if matrix[i][k] && matrix[j][k] && 
-                        (index[i].starts_with("t") || index[j].starts_with("t") || index[k].starts_with("t")) 
-                    {
-                        //nets.push(vec![index[i], index[j], index[k]]);
-                        count += 1;
-                    }
-                }
-            }
-        }
-    }
-    
-    Box::new(count)
-}
-
 /**
  * From https://en.wikipedia.org/wiki/Bron%E2%80%93Kerbosch_algorithm "With Pivoting"
  * 
@@ -100,7 +84,7 @@ fn bron_kerbosch<'a>(
         nr.insert(v);
         let mut np: HashSet<&str> = p.intersection(graph.get(v).unwrap()).cloned().collect();
         let mut nx: HashSet<&str> = x.intersection(graph.get(v).unwrap()).cloned().collect();
-        bron_kerbosch(nr, &mut np, &mut nx, &graph, cliques);
+        bron_kerbosch(nr, &mut np, &mut nx, graph, cliques);
 
         // move v from p to x
         p.remove(v);
@@ -108,34 +92,81 @@ fn bron_kerbosch<'a>(
     }
 }
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let links = parse_input(input);
-    let graph = create_graph(&links);
+/// The network in Graphviz DOT, with the computers and links of `clique` highlighted.
+fn to_dot(links: &[Link], clique: &[&str]) -> String {
+    let mut out = "graph lan {\n\tnode [shape=box];\n".to_string();
 
-    let mut p: HashSet<&str> = graph.keys().cloned().collect();
-    let mut cliques = Vec::new();
+    for node in clique {
+        out += &format!("\t{} [style=filled, fillcolor=gold];\n", node);
+    }
+    for (l, r) in links {
+        let style = if clique.contains(&l.as_str()) && clique.contains(&r.as_str()) { " [color=gold, penwidth=2]" } else { "" };
+        out += &format!("\t{} -- {}{};\n", l, r, style);
+    }
 
-    bron_kerbosch(HashSet::new(), &mut p, &mut HashSet::new(), &graph, &mut cliques);
+    out + "}\n"
+}
 
-    // get largest clique
-    let mut largest: Vec<&str> = cliques
-        .into_iter()
-        .max_by_key(|c| c.len())
-        .unwrap()
-        .into_iter()
-        .collect();
+impl Solution for Solver {
+    type Input = Vec<Link>;
 
-    largest.sort();
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        parse_input(input)
+    }
 
-    Box::new(largest.join(","))
-}
+    fn part1(links: &Self::Input) -> Answer {
+        let (index, matrix) = create_adjacency_matrix(links);
+
+        // let mut nets = Vec::new();
+        let mut count = 0;
+
+        for i in 0..index.len() {
+            for j in i+1..index.len() {
+                if matrix[i][j] {
+                    for k in j+1..index.len() {
+                        if matrix[i][k] && matrix[j][k] && 
+                            (index[i].starts_with('t') || index[j].starts_with('t') || index[k].starts_with('t')) 
+                        {
+                            //nets.push(vec![index[i], index[j], index[k]]);
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+        count.into()
+    }
+
+    fn part2(links: &Self::Input) -> Answer {
+        Self::part2_with(links, &Context::headless())
+    }
+
+    fn part2_with(links: &Self::Input, context: &Context) -> Answer {
+        let graph = create_graph(links);
+
+        let mut p: HashSet<&str> = graph.keys().cloned().collect();
+        let mut cliques = Vec::new();
+
+        bron_kerbosch(HashSet::new(), &mut p, &mut HashSet::new(), &graph, &mut cliques);
 
-    const TEST_INPUT: &str = indoc! {"
+        // get largest clique
+        let mut largest: Vec<&str> = cliques
+            .into_iter()
+            .max_by_key(|c| c.len())
+            .unwrap()
+            .into_iter()
+            .collect();
+
+        largest.sort();
+        context.emit("network", Kind::Dot, to_dot(links, &largest));
+
+        largest.join(",").into()
+    }
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         kh-tc
         qp-kh
         de-cg
@@ -168,15 +199,22 @@ mod tests {
         wh-qp
         tb-vc
         td-yn
-    "};
+    "}).part1("7").part2("co,de,ka,ta"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
 
     #[test] 
     fn test_part1() {
-        assert_eq!(part1(&TEST_INPUT.to_string()).to_string(), "7".to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), 7.into());
     }
 
     #[test] 
     fn test_part2() {
-        assert_eq!(part2(&TEST_INPUT.to_string()).to_string(), "co,de,ka,ta".to_string());
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), "co,de,ka,ta".into());
     }
 }
\ No newline at end of file
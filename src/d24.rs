@@ -1,256 +1,242 @@
-use std::{collections::{HashMap, VecDeque}, str::FromStr};
+use std::collections::HashMap;
 
-use clipboard::ClipboardProvider;
+use indoc::indoc;
 use itertools::Itertools;
-use rayon::str;
+use tracing::{debug, warn};
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
-enum Operation {
-    AND,
-    OR,
-    XOR,
-}
-
-#[derive(Debug, PartialEq, Eq, Clone)]
-struct Gate<'a> {
-    i1: &'a str,
-    op: Operation,
-    i2: &'a str,
-    out: &'a str,
-}
+use crate::{artifacts::Kind, circuit::{self, Circuit, Gate, Operation}, context::Context, error::ParseError, examples::Example, params::Params, solution::{Answer, Solution}};
 
-impl FromStr for Operation {
-    type Err = ();
+pub struct Solver;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "AND" => Ok(Operation::AND),
-            "OR" => Ok(Operation::OR),
-            "XOR" => Ok(Operation::XOR),
-            _ => Err(()),
+/// Wires starting with x, y or z are bits of a number, the rest of the name is the bit index.
+fn check_wire(input: &str, wire: &str) -> Result<(), ParseError> {
+    match wire.strip_prefix(['x', 'y', 'z']) {
+        Some(bit) if bit.parse::<u32>().is_err() => {
+            Err(ParseError::new(format!("Invalid wire {}", wire)).at(input, wire).expected("a bit index like z05"))
         }
+        _ => Ok(()),
     }
 }
 
-impl ToString for Operation {
-    fn to_string(&self) -> String {
-        match self {
-            Operation::AND => "AND",
-            Operation::OR => "OR",
-            Operation::XOR => "XOR"
-        }.to_string()
-    }
-}
-
-fn parse<'a>(input: &'a String) -> (HashMap<&'a str, u8>, Vec<Gate>) {
+fn parse(input: &str) -> Result<(HashMap<String, bool>, Vec<Gate>), ParseError> {
     let mut lines = input.lines();
     
-    let start = lines.by_ref().take_while(|line| !line.is_empty()).filter_map(|line| {
-        let (o, i) = line.split_once(": ").unwrap();
-
-        let v = match i.parse::<u8>() {
-            Ok(v) => v,
-            Err(_) => {
-                panic!("error parsing \"{}\" as u8", i);
-            },
+    let start = lines.by_ref().take_while(|line| !line.is_empty()).map(|line| {
+        let (o, i) = line
+            .split_once(": ")
+            .ok_or_else(|| ParseError::new("Missing ': '").at(input, line).expected("an initial value like x00: 1"))?;
+        check_wire(input, o)?;
+
+        let v = match i {
+            "0" => false,
+            "1" => true,
+            _ => return Err(ParseError::new(format!("Invalid value {}", i)).at(input, i).expected("0 or 1")),
         };
-        Some((o, v))
-    }).collect();
-
-    let _ = lines.by_ref().skip_while(|line| line.is_empty());
-
-    let connections = lines.filter_map(|line| {
-        let (left, right) = line.split_once(" -> ").unwrap();
-        if let Some((i1, op, i2)) = left.split_whitespace().collect_tuple() {
-            Some(Gate {
-                i1: i1,
-                op: op.parse().unwrap(),
-                i2: i2,
-                out: right,
-            })
-        } else {
-            None
-        }
-    }).collect();
-
-    (start, connections)
+        Ok((o.to_string(), v))
+    }).collect::<Result<_, ParseError>>()?;
+
+    let connections = lines.filter(|line| !line.is_empty()).map(|line| {
+        let error = || ParseError::new("Invalid gate").at(input, line).expected("a gate like x00 AND y00 -> z00");
+        let (left, right) = line.split_once(" -> ").ok_or_else(error)?;
+        let (i1, op, i2) = left.split_whitespace().collect_tuple().ok_or_else(error)?;
+        check_wire(input, right)?;
+
+        Ok(Gate {
+            i1: i1.to_string(),
+            op: op.parse().map_err(|_| ParseError::new(format!("Invalid operation {}", op)).at(input, op).expected("AND, OR or XOR"))?,
+            i2: i2.to_string(),
+            out: right.to_string(),
+        })
+    }).collect::<Result<_, ParseError>>()?;
+
+    Ok((start, connections))
 }
 
-pub fn make_num(initial: &str, values: &HashMap<&str, u8>) -> u64 {
-    let mut num: u64 = 0;
-
-    for (k, &v) in values {
-        if k.starts_with(initial) {
-            let shift = k[1..].parse::<u32>().unwrap();
-            num |= (v as u64) << shift;
-        }
+impl Gate {
+    fn is_input(&self) -> bool {
+        [&self.i1, &self.i2].iter().all(|wire| wire.starts_with(['x', 'y']))
     }
 
-    num
+    fn is_lowest_bit(&self) -> bool {
+        [&self.i1, &self.i2].iter().all(|wire| wire.strip_prefix(['x', 'y']) == Some("00"))
+    }
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let (mut values, connections) = parse(input);
+/// A gate whose output does not fit the adder, with the rule it breaks.
+struct Suspect<'a> {
+    gate: &'a Gate,
+    rule: &'static str,
+}
 
-    let mut queue = VecDeque::new();
-    for gate in connections.iter() {
-        queue.push_back(gate);
+/// The gates that break the structure of a ripple-carry adder. Bit `i` sums `xi XOR yi` with the
+/// carry of the bit below in an XOR writing `zi`, and carries `xi AND yi` OR the AND of the sum and
+/// the carry below. The lowest bit has no carry in, the carry out of the highest bit is the last `z`.
+fn suspects(gates: &[Gate]) -> Vec<Suspect<'_>> {
+    let bits = gates.iter()
+        .flat_map(|gate| [&gate.i1, &gate.i2])
+        .filter_map(|wire| wire.strip_prefix('x')?.parse::<usize>().ok())
+        .max()
+        .map_or(0, |bit| bit + 1);
+    let last = format!("z{:0>2}", bits);
+
+    // the operations of the gates reading each wire
+    let mut readers: HashMap<&str, Vec<Operation>> = HashMap::new();
+    for gate in gates {
+        readers.entry(&gate.i1).or_default().push(gate.op);
+        readers.entry(&gate.i2).or_default().push(gate.op);
     }
-
-    while let Some(gate) = queue.pop_front() {
-        if values.contains_key(&gate.i1) && values.contains_key(&gate.i2) {
-            let v1 = values[&gate.i1];
-            let v2 = values[&gate.i2];
-            let result = match gate.op {
-                Operation::AND => v1 & v2,
-                Operation::OR => v1 | v2,
-                Operation::XOR => v1 ^ v2,
-            };
-            values.insert(gate.out, result);
-        } else {
-            queue.push_back(gate);
+    let feeds = |gate: &Gate, op| readers.get(gate.out.as_str()).is_some_and(|ops| ops.contains(&op));
+
+    let rule = |gate: &Gate| -> Option<&'static str> {
+        let output = gate.out.starts_with('z');
+
+        match gate.op {
+            _ if gate.out == last => (gate.op != Operation::Or && bits > 1).then_some("only an OR gate writes the carry out of the highest bit"),
+            Operation::Xor if gate.is_lowest_bit() => (gate.out != "z00").then_some("the lowest bits are summed straight into z00"),
+            Operation::Xor if gate.is_input() => (!feeds(gate, Operation::Xor)).then_some("the sum of two input bits has to be added to a carry by an XOR gate"),
+            Operation::Xor => (!output).then_some("an XOR gate adding a carry has to write an output bit"),
+            _ if output => Some("only XOR gates write an output bit"),
+            Operation::And if gate.is_lowest_bit() => (!feeds(gate, Operation::Xor)).then_some("a carry has to be added to the next bit by an XOR gate"),
+            Operation::And => (!feeds(gate, Operation::Or)).then_some("a partial carry has to go through an OR gate"),
+            Operation::Or => (!feeds(gate, Operation::Xor)).then_some("a carry has to be added to the next bit by an XOR gate"),
         }
-    }
-    
-    Box::new(make_num("z", &values))
+    };
+
+    gates.iter().filter_map(|gate| Some(Suspect { gate, rule: rule(gate)? })).collect()
 }
 
+/// The circuit as a Mermaid flowchart, with the gates of `suspects` highlighted.
+fn flowchart(gates: &[Gate], suspects: &[Suspect]) -> String {
+    let mut wires: Vec<&str> = gates.iter().flat_map(|gate| [&gate.i1, &gate.i2, &gate.out]).map(String::as_str).filter(|wire| wire.starts_with(['x', 'y', 'z'])).collect();
+    wires.sort();
+    wires.dedup();
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    let (_, connections) = parse(input);
-    
-    let mut nodes = Vec::new();
-    let mut gates = Vec::new();
-    let mut edges = Vec::new();
-    
-    #[derive(Debug, Hash, PartialEq, Eq)]
-    struct Node {
-        id: String, 
-        label: String, 
-        shape: &'static str,
-    }
+    // gates are named after the wire they write, inputs and outputs keep their own names
+    let node = |wire: &str| if wire.starts_with(['x', 'y']) { wire.to_string() } else { format!("_{}", wire) };
 
-    #[derive(Debug, Hash, PartialEq, Eq)]
-    struct Edge {
-        label: String, 
-        from: String,
-        to: String,
-        to_type: String,
+    let mut txt = "flowchart LR\n".to_string();
+    for wire in wires {
+        txt += &format!("\t{}@{{ shape: circle, label: {} }}\n", wire, wire);
     }
-    let switches = [
-        ("z05", "gdd"),
-        ("z09", "cwt"),
-        ("css", "jmv"),
-        ("z37", "pqt"),
-    ];
-
-    // apply switches
-    let connections = connections.iter().map(|conn| {
-        let mut conn = conn.clone();
-        for &(a, b) in switches.iter() {
-            if conn.out == a {
-                conn.out = b;
-            } else if conn.out == b {
-                conn.out = a;
-            }
+    for gate in gates.iter().sorted_by_key(|gate| gate.op.to_string()) {
+        txt += &format!("\t{}@{{ shape: rect, label: {} }}\n", node(&gate.out), gate.op);
+    }
+    for gate in gates.iter().sorted_by_key(|gate| gate.op.to_string()) {
+        for input in [&gate.i1, &gate.i2] {
+            txt += &format!("\t{} --> |{}| {}\n", node(input), input, node(&gate.out));
         }
-        conn
-    }).collect::<Vec<_>>();
-    
-    // add inputs
-    for i in 0..=44 {
-        for c in ['x', 'y'] {
-            nodes.push(Node {
-                id: format!{"{}{:02}", c, i},
-                label: format!{"{}{:02}", c, i},
-                shape: "circle"
-            });
+        if gate.out.starts_with('z') {
+            txt += &format!("\t{} --> |{}| {}\n", node(&gate.out), gate.out, gate.out);
         }
     }
+    for suspect in suspects {
+        txt += &format!("\tstyle {} fill:#f66\n", node(&suspect.gate.out));
+    }
+
+    txt
+}
 
-    for conn in connections.iter() {
-        
-        let id = format!("_{}", conn.out);
-
-        gates.push(Node {
-            id: id.clone(),
-            label: conn.op.to_string(),
-            shape: "rect"
-        });
-        
-        edges.push(Edge {
-            label: conn.i1.to_string(),
-            from: match conn.i1.chars().nth(0).unwrap() {'x' | 'y' => conn.i1.to_string(), _=> format!("_{}", conn.i1)},
-            to: id.clone(),
-            to_type: conn.op.to_string(),
-        });
-        
-        edges.push(Edge {
-            label: conn.i2.to_string(),
-            from: match conn.i1.chars().nth(0).unwrap() {'x' | 'y' => conn.i2.to_string(), _=> format!("_{}", conn.i2)},
-            to: id.clone(),
-            to_type: conn.op.to_string(),
-        });
-
-        if conn.out.chars().nth(0) == Some('z') {
-            edges.push(Edge {
-                label: conn.out.to_string(),
-                from: id.clone(),
-                to: conn.out.to_string(),
-                to_type: "output".to_string(),
-            });
+/// The gates with every output wire swapped in `swaps`, put back where it belongs.
+fn swap_outputs(gates: &[Gate], swaps: &[(&str, &str)]) -> Vec<Gate> {
+    gates.iter().map(|gate| {
+        let mut gate = gate.clone();
+        for &(a, b) in swaps {
+            if gate.out == a {
+                gate.out = b.to_string();
+            } else if gate.out == b {
+                gate.out = a.to_string();
+            }
         }
+        gate
+    }).collect()
+}
+
+/// The `swaps` pairs of `suspects` whose outputs swapped back make the gates add, trying each
+/// choice of wires among the suspects and each way to pair them up, as the rules can flag a gate
+/// that is fine. A pairing that makes a loop is no repair.
+fn repair<'a>(gates: &[Gate], suspects: &[&'a str], swaps: usize) -> Option<Vec<(&'a str, &'a str)>> {
+    fn pairings<'a>(wires: &[&'a str], pairs: &mut Vec<(&'a str, &'a str)>, check: &mut impl FnMut(&[(&'a str, &'a str)]) -> bool) -> bool {
+        let Some((&first, rest)) = wires.split_first() else {
+            return check(pairs);
+        };
+
+        (0..rest.len()).any(|i| {
+            let others: Vec<&str> = rest.iter().enumerate().filter(|&(j, _)| j != i).map(|(_, &w)| w).collect();
+            pairs.push((first, rest[i]));
+            let found = pairings(&others, pairs, check);
+            if !found {
+                pairs.pop();
+            }
+            found
+        })
     }
 
-    // sort gates by operation
-    gates.sort_by(|a,b| a.label.cmp(&b.label));
+    let mut check = |pairs: &[(&str, &str)]| Circuit::new(&swap_outputs(gates, pairs)).is_ok_and(|circuit| circuit.check_adder().is_ok());
 
-    // sort edges by operation
-    edges.sort_by(|a,b| a.to_type.cmp(&b.to_type));
+    suspects.iter().copied().combinations(2 * swaps).find_map(|wires| {
+        let mut pairs = Vec::new();
+        pairings(&wires, &mut pairs, &mut check).then_some(pairs)
+    })
+}
 
-    // add to nodes
-    nodes.extend(gates.into_iter());
+/// The initial values, the gates, and how many pairs of gates have their outputs swapped.
+pub struct Device {
+    values: HashMap<String, bool>,
+    gates: Vec<Gate>,
+    circuit: Circuit,
+    swaps: usize,
+}
 
-    // add outputs
-    for i in 0..=44 {
-        nodes.push(Node {
-            id: format!{"z{:02}", i},
-            label: format!{"z{:02}", i},
-            shape: "circle"
-        });
-    }
+impl Solution for Solver {
+    type Input = Device;
 
-    let mut txt = "flowchart LR\n".to_string();
+    const PARAMS: &'static [(&'static str, &'static str)] = &[("swaps", "4")];
 
-    for node in nodes.iter() {
-        txt.push_str(&format!("\t{}@{{ shape: {}, label: {} }}\n", node.id, node.shape, node.label));
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError> {
+        let (values, gates) = parse(input)?;
+        let circuit = Circuit::new(&gates).map_err(ParseError::new)?;
+
+        if let Some(wire) = circuit.inputs().find(|wire| !values.contains_key(*wire)) {
+            return Err(ParseError::new(format!("Wire {} is read but never set", wire)).at(input, wire).expected("an initial value or a gate writing it"));
+        }
+
+        Ok(Device { values, gates, circuit, swaps: params.get("swaps")? })
     }
-    for edge in edges.iter() {
-        txt.push_str(&format!("\t{} --> |{}| {}\n", edge.from, edge.label, edge.to));
+
+    fn part1(device: &Self::Input) -> Answer {
+        let values = device.circuit.evaluate(&device.values).unwrap();
+
+        circuit::number(&values, "z").into()
     }
 
-    let mut clip = clipboard::ClipboardContext::new().unwrap();
-    clip.set_contents(txt).unwrap();
+    fn part2(device: &Self::Input) -> Answer {
+        Self::part2_with(device, &Context::headless())
+    }
 
-    println!("Mermaid flowchart copied to clipboard");
+    fn part2_with(device: &Self::Input, context: &Context) -> Answer {
+        let suspects = suspects(&device.gates);
+        for suspect in &suspects {
+            context.print(format!("{}: {}", suspect.gate, suspect.rule));
+        }
 
-    let mut result = switches.into_iter().fold(vec![], |mut acc, (a,b)| {
-        acc.push(a);
-        acc.push(b);
-        acc
-    });
+        let txt = flowchart(&device.gates, &suspects);
+        context.emit("flowchart", Kind::Mermaid, &txt);
+        context.copy_to_clipboard("Flowchart", txt);
 
-    result.sort();
+        // the rules only tell which gates look wrong, swapping them back has to make the circuit add
+        let wires: Vec<&str> = suspects.iter().map(|suspect| suspect.gate.out.as_str()).collect();
+        let Some(pairs) = repair(&device.gates, &wires, device.swaps) else {
+            warn!("No {} swaps among {} make the circuit add", device.swaps, wires.iter().sorted().join(", "));
+            return Answer::Unsolved;
+        };
+        debug!(?pairs, "repaired");
 
-    Box::new(result.join(","))
+        pairs.iter().flat_map(|&(a, b)| [a, b]).sorted().join(",").into()
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
-
-    const TEST_INPUT: &str = indoc! {"
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         x00: 1
         x01: 1
         x02: 1
@@ -261,9 +247,8 @@ mod tests {
         x00 AND y00 -> z00
         x01 XOR y01 -> z01
         x02 OR y02 -> z02
-    "};
-
-    const TEST_INPUT2: &str = indoc! {"
+    "}).part1("4"),
+    Example::new(indoc! {"
         x00: 1
         x01: 0
         x02: 1
@@ -311,16 +296,86 @@ mod tests {
         hwm AND bqk -> z03
         tgd XOR rvg -> z12
         tnw OR pbm -> gnj
-    "};
+    "}).part1("2024"),
+    // a 4-bit adder with two pairs of outputs swapped: z02 with c02, and a03 with s03
+    Example::new(indoc! {"
+        x00: 1
+        x01: 0
+        x02: 1
+        x03: 1
+        y00: 1
+        y01: 1
+        y02: 0
+        y03: 1
+
+        x00 XOR y00 -> z00
+        x00 AND y00 -> c00
+        x01 XOR y01 -> s01
+        x01 AND y01 -> a01
+        s01 XOR c00 -> z01
+        s01 AND c00 -> b01
+        a01 OR b01 -> c01
+        x02 XOR y02 -> s02
+        x02 AND y02 -> a02
+        s02 XOR c01 -> c02
+        s02 AND c01 -> b02
+        a02 OR b02 -> z02
+        x03 XOR y03 -> a03
+        x03 AND y03 -> s03
+        s03 XOR c02 -> z03
+        s03 AND c02 -> b03
+        a03 OR b03 -> z04
+    "}).part2("a03,c02,s03,z02").params(&[("swaps", "2")]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
+
+    const TEST_INPUT2: &str = EXAMPLES[1].input;
+
+    const TEST_RESULT_2: &str = "a03,c02,s03,z02";
+
+    #[test]
+    fn test_repair() {
+        // an OR gate nothing reads breaks the carry rule without being swapped
+        let input = format!("{}x00 OR y00 -> dbg\n", EXAMPLES[2].input);
+        let device = Solver::parse_with(&input, &Params::new(&[("swaps", "2")])).unwrap();
+        assert_eq!(suspects(&device.gates).len(), 5);
+        assert_eq!(Solver::part2(&device), TEST_RESULT_2.into());
+
+        // one swap cannot repair two
+        let device = Solver::parse_with(EXAMPLES[2].input, &Params::new(&[("swaps", "1")])).unwrap();
+        assert_eq!(Solver::part2(&device), Answer::Unsolved);
+    }
 
     #[test] 
     fn test_part1() {
-        assert_eq!(part1(&TEST_INPUT.to_string()).to_string(), "4".to_string());
-        assert_eq!(part1(&TEST_INPUT2.to_string()).to_string(), "2024".to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), 4.into());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT2).unwrap()), 2024.into());
     }
 
     #[test] 
     fn test_part2() {
-        assert_eq!(part2(&TEST_INPUT2.to_string()).to_string(), "co,de,ka,ta".to_string());
+        assert_eq!(Solver::part2(&EXAMPLES[2].parse::<Solver>().unwrap()), TEST_RESULT_2.into());
+    }
+
+    #[test]
+    fn test_suspects() {
+        let device = EXAMPLES[2].parse::<Solver>().unwrap();
+        let rules: Vec<_> = suspects(&device.gates).iter().map(|s| (s.gate.out.as_str(), s.rule)).collect();
+
+        assert_eq!(rules, [
+            ("c02", "an XOR gate adding a carry has to write an output bit"),
+            ("z02", "only XOR gates write an output bit"),
+            ("a03", "the sum of two input bits has to be added to a carry by an XOR gate"),
+            ("s03", "a partial carry has to go through an OR gate"),
+        ]);
+
+        // a wire name is not always ASCII
+        let gate = Gate { i1: "é00".to_string(), op: Operation::And, i2: "y00".to_string(), out: "z00".to_string() };
+        assert!(!gate.is_lowest_bit());
     }
 }
\ No newline at end of file
@@ -1,60 +1,132 @@
-fn parse(input: &String) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
-    let mut locks = Vec::new();
-    let mut keys = Vec::new();
-    let mut lines = input.lines();
+use std::collections::HashMap;
 
+use indoc::indoc;
 
-    while let Some(line) = lines.next() {
-        let block: Vec<Vec<char>> = lines.by_ref().take_while(|l| !l.is_empty()).map(|l| l.chars().collect()).collect();
-        let mut thing = vec![0u8;5];
+use crate::{error::ParseError, examples::Example, grid::Grid, params::Params, solution::{Answer, Solution}};
+
+pub struct Solver;
+
+/// The locks and keys of an input as bitmasks of their pins, a bit per cell between the solid
+/// row and the empty one, with how many schematics share each mask. A key fits a lock when no
+/// cell is taken by both.
+pub struct Schematics {
+    locks: HashMap<u64, usize>,
+    keys: HashMap<u64, usize>,
+}
 
-        if line.chars().nth(0) == Some('#') {
-            for i in 0..5 {
-                thing[i] = block.iter().position(|line| line[i] == '.').unwrap() as u8;
+/// A block of the input: a lock has its pins hanging from a solid top row, a key has them
+/// standing on a solid bottom row.
+struct Schematic {
+    lock: bool,
+    heights: Vec<usize>,
+}
+
+impl Schematic {
+    fn parse(input: &str, lines: &[&str], size: (usize, usize)) -> Result<Schematic, ParseError> {
+        let (width, height) = size;
+
+        let mut cells = Vec::new();
+        for l in lines {
+            if let Some((i, c)) = l.char_indices().find(|&(_, c)| c != '#' && c != '.') {
+                return Err(ParseError::new(format!("Invalid character {}", c)).at(input, &l[i..i + c.len_utf8()]).expected("# or ."));
             }
-            locks.push(thing);
-        } else {
-            for i in 0..5 {
-                thing[i] = 5 - block.iter().position(|line| line[i] == '#').unwrap() as u8;
+            if l.len() != width {
+                return Err(ParseError::new("Invalid schematic row").at(input, l).expected(format!("{} columns like the first schematic", width)));
             }
-            keys.push(thing);
+            cells.extend(l.bytes().map(|b| b == b'#'));
         }
+        if lines.len() != height {
+            return Err(ParseError::new("Invalid schematic height").at(input, lines[0]).expected(format!("{} rows like the first schematic", height)));
+        }
+
+        let grid = Grid::from_vec(width, height, cells);
+        let full = |row: &[bool], pin| row.iter().all(|&cell| cell == pin);
+        let (top, bottom) = (grid.rows().next().unwrap(), grid.rows().last().unwrap());
+        let lock = match (full(top, true) && full(bottom, false), full(top, false) && full(bottom, true)) {
+            (true, _) => true,
+            (_, true) => false,
+            _ => return Err(ParseError::new("Neither a lock nor a key").at(input, lines[0]).expected("a row of # at the top or at the bottom")),
+        };
+
+        let mut heights = Vec::new();
+        for (i, column) in grid.columns().enumerate() {
+            // read from the solid row, a pin is a run of # followed by nothing but .
+            let mut column: Vec<bool> = column.copied().collect();
+            if !lock {
+                column.reverse();
+            }
+
+            let pin = column.iter().take_while(|&&cell| cell).count();
+            if column[pin..].contains(&true) {
+                return Err(ParseError::new(format!("Column {} of the schematic has a gap in its pin", i + 1)).at(input, lines[0]));
+            }
+            heights.push(pin - 1);
+        }
+
+        Ok(Schematic { lock, heights })
     }
 
-    (locks, keys)
+    /// The cells the pins take up, `space` rows below the lock's top or above the key's bottom.
+    fn mask(&self, space: usize) -> u64 {
+        let width = self.heights.len();
+
+        self.heights.iter().enumerate().fold(0, |mask, (x, &height)| {
+            let rows = if self.lock { 0..height } else { space - height..space };
+            rows.fold(mask, |mask, y| mask | 1 << (y * width + x))
+        })
+    }
 }
 
-pub fn part1(input: &String) -> Box<dyn ToString> {
-    let (locks, keys) = parse(input);
+fn parse(input: &str) -> Result<Schematics, ParseError> {
+    let mut locks = HashMap::new();
+    let mut keys = HashMap::new();
+    let mut size = None;
+    let mut lines = input.lines();
 
-    let fits = |key: &Vec<u8>, lock: &Vec<u8>| {
-        key.iter().zip(lock.iter()).all(|(a,b)| a + b <= 5)
-    };
+    while let Some(line) = lines.next() {
+        if line.is_empty() {
+            continue;
+        }
+        let block: Vec<&str> = std::iter::once(line).chain(lines.by_ref().take_while(|l| !l.is_empty())).collect();
 
-    let mut count = 0;
-    for lock in locks.iter() {
-        for key in keys.iter() {
-            if fits(key, lock) {
-                count += 1;
-            }
+        // the first schematic sets the size of all of them
+        let (width, height) = *size.get_or_insert((line.len(), block.len()));
+        if height < 2 || width * (height - 2) > u64::BITS as usize {
+            return Err(ParseError::new(format!("A schematic of {}x{} does not fit in a bitmask", width, height)).at(input, line).expected("at most 64 cells between the top and bottom rows"));
         }
+
+        let schematic = Schematic::parse(input, &block, (width, height))?;
+        let mask = schematic.mask(height - 2);
+        let masks = if schematic.lock { &mut locks } else { &mut keys };
+        *masks.entry(mask).or_insert(0) += 1;
     }
 
-    Box::new(count)
+    Ok(Schematics { locks, keys })
 }
 
+impl Solution for Solver {
+    type Input = Schematics;
 
-pub fn part2(input: &String) -> Box<dyn ToString> {
-    
-    Box::new(0)
-}
+    fn parse_with(input: &str, _params: &Params) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
 
-#[cfg(test)]
-mod tests {
-    use indoc::indoc;
-    use super::*;
+    fn part1(schematics: &Self::Input) -> Answer {
+        // each distinct lock is tried once against each distinct key, the counts make up for the copies
+        schematics.locks.iter()
+            .map(|(&lock, &locks)| schematics.keys.iter().filter(|&(&key, _)| lock & key == 0).map(|(_, &keys)| locks * keys).sum::<usize>())
+            .sum::<usize>()
+            .into()
+    }
 
-    const TEST_INPUT: &str = indoc! {"
+    fn part2(_: &Self::Input) -> Answer {
+        // the last day only has one puzzle
+        Answer::NoPuzzle
+    }
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example::new(indoc! {"
         #####
         .####
         .####
@@ -94,15 +166,44 @@ mod tests {
         #.#..
         #.#.#
         #####
-    "};
+    "}).part1("3"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = EXAMPLES[0].input;
 
     #[test] 
     fn test_part1() {
-        assert_eq!(part1(&TEST_INPUT.to_string()).to_string(), "3".to_string());
+        assert_eq!(Solver::part1(&Solver::parse(TEST_INPUT).unwrap()), 3.into());
     }
 
     #[test] 
     fn test_part2() {
+        assert_eq!(Solver::part2(&Solver::parse(TEST_INPUT).unwrap()), Answer::NoPuzzle);
+    }
 
+    #[test]
+    fn test_parse() {
+        // the first lock and key of the example only overlap in the third row of the last column
+        let lock = Schematic { lock: true, heights: vec![0, 5, 3, 4, 3] };
+        let key = Schematic { lock: false, heights: vec![5, 0, 2, 1, 3] };
+        assert_eq!(lock.mask(5) & key.mask(5), 1 << (2 * 5 + 4));
+
+        // other sizes than the puzzle's work as long as all schematics share it
+        let small = Solver::parse("###\n#.#\n...\n\n...\n.#.\n###").unwrap();
+        assert_eq!(Solver::part1(&small), 1.into());
+
+        // copies of a schematic share a mask and still count once each
+        let copies = Solver::parse("###\n#.#\n...\n\n###\n#.#\n...\n\n...\n.#.\n###\n\n...\n...\n###\n\n...\n...\n###").unwrap();
+        assert_eq!((copies.locks.len(), copies.keys.len()), (1, 2));
+        assert_eq!(Solver::part1(&copies), 6.into());
+
+        let error = |input| Solver::parse(input).err().unwrap().message;
+        assert_eq!(error("###\n#.#\n...\n\n....\n.#..\n####"), "Invalid schematic row");
+        assert_eq!(error("###\n#.#\n###"), "Neither a lock nor a key");
+        assert_eq!(error("###\n.#.\n#..\n..."), "Column 1 of the schematic has a gap in its pin");
     }
 }
\ No newline at end of file
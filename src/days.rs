@@ -3,17 +3,16 @@ macro_rules! days {
     ($($day:ident),*) => {
         $(mod $day;)*
         static DAYS: LazyLock<Vec<Day>> = LazyLock::new(|| {
-            let parts: Vec<(&str, fn(&String) -> Box<dyn ToString>, fn(&String) -> Box<dyn ToString>)> = vec![
+            vec![
                 $(
-                    (stringify!($day), $day::part1, $day::part2),
+                    Day {
+                        num: stringify!($day).trim_start_matches("d").parse().unwrap(),
+                        parse: solution::parse::<$day::Solver>,
+                        params: <$day::Solver as solution::Solution>::PARAMS,
+                        examples: $day::EXAMPLES,
+                    },
                 )*
-            ];
-
-            parts.into_iter().map(|(d, part1, part2)| Day { 
-                num: d.trim_start_matches("d").parse().unwrap(),
-                part1,
-                part2,
-            }).collect()
+            ]
         });
     };
-}
\ No newline at end of file
+}
@@ -0,0 +1,155 @@
+use std::fmt::{self, Display};
+
+/// Error returned when a puzzle input cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    /// The day whose input failed to parse, filled in by the runner.
+    pub day: Option<usize>,
+    pub location: Option<Location>,
+    /// What the parser was looking for instead of the offending text.
+    pub expected: Option<String>,
+}
+
+/// Where in the input a parse error happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+    /// The whole line containing the error.
+    pub text: String,
+    /// How many characters the offending text spans, at least one.
+    pub len: usize,
+}
+
+impl Location {
+    /// Finds `part` in `input`, preferably by address when it was sliced from `input`,
+    /// otherwise by searching for the text.
+    fn find(input: &str, part: &str) -> Option<Location> {
+        let start = (part.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|&offset| offset + part.len() <= input.len())
+            .or_else(|| input.find(part))?;
+
+        let line_start = input[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[start..].find('\n').map_or(input.len(), |i| start + i);
+
+        Some(Location {
+            line: input[..start].matches('\n').count() + 1,
+            column: input[line_start..start].chars().count() + 1,
+            text: input[line_start..line_end].trim_end_matches('\r').to_string(),
+            len: part.chars().take_while(|&c| c != '\n').count().max(1),
+        })
+    }
+}
+
+impl ParseError {
+    pub fn new(message: impl Into<String>) -> Self {
+        ParseError { message: message.into(), day: None, location: None, expected: None }
+    }
+
+    /// Points the error at `part`, a piece of `input`, unless an inner parser already did.
+    pub fn at(mut self, input: &str, part: &str) -> Self {
+        if self.location.is_none() {
+            self.location = Location::find(input, part);
+        }
+        self
+    }
+
+    pub fn expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+
+    pub fn for_day(mut self, day: usize) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    /// A multi-line diagnostic quoting the offending line with a caret under the error.
+    pub fn render(&self) -> String {
+        let mut out = self.message.clone();
+
+        let mut origin = Vec::new();
+        if let Some(day) = self.day {
+            origin.push(format!("day {:0>2} input", day));
+        }
+        if let Some(location) = &self.location {
+            origin.push(format!("line {}, column {}", location.line, location.column));
+        }
+        if !origin.is_empty() {
+            out += &format!("\n --> {}", origin.join(", "));
+        }
+
+        match &self.location {
+            Some(location) => {
+                let number = location.line.to_string();
+                let gutter = " ".repeat(number.len());
+                let available = location.text.chars().count().saturating_sub(location.column - 1);
+
+                out += &format!("\n{} |\n{} | {}\n{} | {}{}", gutter, number, location.text, gutter,
+                    " ".repeat(location.column - 1), "^".repeat(location.len.min(available).max(1)));
+
+                if let Some(expected) = &self.expected {
+                    out += &format!(" expected {}", expected);
+                }
+            }
+            None => {
+                if let Some(expected) = &self.expected {
+                    out += &format!("\n  = expected {}", expected);
+                }
+            }
+        }
+
+        out
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+
+        if let Some(location) = &self.location {
+            write!(f, " at line {}, column {}", location.line, location.column)?;
+        }
+        if let Some(expected) = &self.expected {
+            write!(f, " (expected {})", expected)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "1,2\n3;4\n5,6";
+
+    #[test]
+    fn test_at() {
+        let error = ParseError::new("Invalid separator").at(INPUT, &INPUT[5..6]).expected("','");
+
+        let location = error.location.as_ref().unwrap();
+        assert_eq!((location.line, location.column, location.len), (2, 2, 1));
+        assert_eq!(location.text, "3;4");
+        assert_eq!(error.to_string(), "Invalid separator at line 2, column 2 (expected ',')");
+
+        // copied text is found by searching, an existing location is kept
+        let error = ParseError::new("Bad line").at(INPUT, "5,6");
+        assert_eq!(error.location.as_ref().map(|l| l.line), Some(3));
+        assert_eq!(error.clone().at(INPUT, "1"), error);
+    }
+
+    #[test]
+    fn test_render() {
+        let error = ParseError::new("Invalid separator").at(INPUT, &INPUT[5..6]).expected("','").for_day(7);
+
+        assert_eq!(error.render(), "Invalid separator\n --> day 07 input, line 2, column 2\n  |\n2 | 3;4\n  |  ^ expected ','");
+        assert_eq!(ParseError::new("Empty input").expected("a number").render(), "Empty input\n  = expected a number");
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{answers::Answers, params::Params};
+#[cfg(test)]
+use crate::{error::ParseError, solution::Solution};
+
+/// A puzzle example with the answers given in its description.
+///
+/// Each day lists its examples in an `EXAMPLES` constant, which the day's tests and the
+/// `--example` flag both use. Expected answers are written the way they are printed.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+    /// Values the example uses instead of the puzzle's, like a smaller grid.
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+impl Example {
+    pub const fn new(input: &'static str) -> Example {
+        Example { input, part1: None, part2: None, params: &[] }
+    }
+
+    pub const fn part1(self, expected: &'static str) -> Example {
+        Example { part1: Some(expected), ..self }
+    }
+
+    pub const fn part2(self, expected: &'static str) -> Example {
+        Example { part2: Some(expected), ..self }
+    }
+
+    pub const fn params(self, params: &'static [(&'static str, &'static str)]) -> Example {
+        Example { params, ..self }
+    }
+
+    pub fn expected(&self, part: u8) -> Option<&'static str> {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => None,
+        }
+    }
+
+    /// The parts this example has an answer for.
+    pub fn parts(&self) -> Vec<u8> {
+        [1, 2].into_iter().filter(|&n| self.expected(n).is_some()).collect()
+    }
+
+    pub fn answers(&self) -> Answers {
+        Answers::new(self.part1, self.part2)
+    }
+
+    /// Applies the example's parameters on top of `params`.
+    pub fn apply(&self, params: Params) -> Result<Params, String> {
+        params.with(self.params)
+    }
+
+    /// Parses the example the way the runner would with the day's default parameters.
+    #[cfg(test)]
+    pub fn parse<S: Solution>(&self) -> Result<S::Input, ParseError> {
+        let params = self.apply(Params::new(S::PARAMS)).map_err(ParseError::new)?;
+
+        S::parse_with(self.input, &params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers::Verdict, solution::Answer};
+
+    #[test]
+    fn test_example() {
+        const EXAMPLE: Example = Example::new("1 2").part2("ab,cd");
+
+        assert_eq!(EXAMPLE.parts(), [2]);
+        assert_eq!(EXAMPLE.answers().check(2, &Answer::from("ab,cd")), Verdict::Pass);
+        assert_eq!(EXAMPLE.answers().check(1, &Answer::from(3)), Verdict::Unknown);
+    }
+}
@@ -0,0 +1,154 @@
+use std::{env, fs, path::{Path, PathBuf}, time::Duration};
+
+use crate::config::Config;
+
+/// Environment variable holding the session cookie of the puzzle website, it wins over `aoc.toml`.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+const YEAR: u16 = 2024;
+
+/// Something that can hand out the puzzle input of a day.
+pub trait Provider {
+    fn fetch(&self, day: usize) -> Result<String, String>;
+}
+
+/// Downloads inputs from the puzzle website, or any server laid out like it.
+pub struct Http {
+    base_url: String,
+    session: String,
+}
+
+impl Http {
+    pub fn new(base_url: &str, session: &str) -> Http {
+        Http { base_url: base_url.trim_end_matches('/').to_string(), session: session.trim().to_string() }
+    }
+
+    /// The provider set up from `AOC_SESSION` or `aoc.toml`, there is none without a session token.
+    pub fn from_config(config: &Config) -> Option<Http> {
+        let session = env::var(SESSION_ENV).ok().or_else(|| config.fetch.session.clone()).filter(|s| !s.trim().is_empty())?;
+
+        Some(Http::new(config.fetch.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL), &session))
+    }
+
+    fn url(&self, day: usize) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, YEAR, day)
+    }
+}
+
+impl Provider for Http {
+    fn fetch(&self, day: usize) -> Result<String, String> {
+        let url = self.url(day);
+        let agent: ureq::Agent = ureq::Agent::config_builder().timeout_global(Some(Duration::from_secs(30))).build().into();
+
+        let mut response = agent
+            .get(&url)
+            .header("Cookie", format!("session={}", self.session))
+            .header("User-Agent", concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+            .call()
+            .map_err(|e| format!("Could not download {}: {}", url, e))?;
+
+        response.body_mut().read_to_string().map_err(|e| format!("Could not download {}: {}", url, e))
+    }
+}
+
+/// Keeps the inputs of another provider as `dNN.txt` files in a directory, so each one is only fetched once.
+pub struct Cached<P> {
+    dir: PathBuf,
+    inner: P,
+}
+
+impl<P: Provider> Cached<P> {
+    pub fn new(dir: &Path, inner: P) -> Cached<P> {
+        Cached { dir: dir.to_path_buf(), inner }
+    }
+
+    pub fn path(&self, day: usize) -> PathBuf {
+        self.dir.join(format!("d{:0>2}.txt", day))
+    }
+
+    /// The path of the day's input, fetching it first when it is not there yet.
+    pub fn get(&self, day: usize) -> Result<PathBuf, String> {
+        let path = self.path(day);
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        let input = self.inner.fetch(day)?;
+
+        // written aside and renamed so an interrupted download never leaves half an input behind
+        fs::create_dir_all(&self.dir).map_err(|e| format!("Could not create {}: {}", self.dir.display(), e))?;
+        let partial = path.with_extension("part");
+        fs::write(&partial, input).map_err(|e| format!("Could not write {}: {}", partial.display(), e))?;
+        fs::rename(&partial, &path).map_err(|e| format!("Could not write {}: {}", path.display(), e))?;
+
+        Ok(path)
+    }
+}
+
+impl<P: Provider> Provider for Cached<P> {
+    fn fetch(&self, day: usize) -> Result<String, String> {
+        let path = self.get(day)?;
+
+        fs::read_to_string(&path).map_err(|e| format!("Could not read input file {}: {}", path.display(), e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, io::{BufRead, BufReader, Write}, net::TcpListener, thread};
+
+    struct Counting(Cell<usize>);
+
+    impl Provider for Counting {
+        fn fetch(&self, day: usize) -> Result<String, String> {
+            self.0.set(self.0.get() + 1);
+            Ok(format!("input {}", day))
+        }
+    }
+
+    #[test]
+    fn test_cached() {
+        let dir = env::temp_dir().join(format!("aoc-fetch-{}", std::process::id()));
+        let cached = Cached::new(&dir, Counting(Cell::new(0)));
+
+        assert_eq!(cached.fetch(7), Ok("input 7".to_string()));
+        assert_eq!(cached.fetch(7), Ok("input 7".to_string()));
+        assert_eq!(cached.get(7), Ok(dir.join("d07.txt")));
+        assert_eq!(cached.inner.0.get(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_http() {
+        // a stand-in for the puzzle website answering a single request
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            for line in BufReader::new(&stream).lines() {
+                let line = line.unwrap();
+                if line.is_empty() {
+                    break;
+                }
+                request.push(line);
+            }
+
+            let body = "1 2 3\n";
+            write!(&stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body).unwrap();
+            request
+        });
+
+        let input = Http::new(&format!("{}/", base_url), "secret\n").fetch(7);
+        let request = server.join().unwrap();
+
+        assert_eq!(input, Ok("1 2 3\n".to_string()));
+        assert_eq!(request[0], "GET /2024/day/7/input HTTP/1.1");
+        assert!(request.iter().any(|line| line.eq_ignore_ascii_case("cookie: session=secret")));
+    }
+}
@@ -0,0 +1,316 @@
+use std::{fmt::{self, Display}, ops::{Add, AddAssign, Mul, Neg, Rem, Sub, SubAssign}};
+
+/// A point (or vector) on a 2D plane, `y` grows downwards like the puzzle inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point<T = i64> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Point<T>) {
+        self.x += rhs.x;
+        self.y += rhs.y;
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Point<T>;
+
+    fn sub(self, rhs: Point<T>) -> Point<T> {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Point<T>) {
+        self.x -= rhs.x;
+        self.y -= rhs.y;
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Point<T>;
+
+    fn mul(self, rhs: T) -> Point<T> {
+        Point::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Point<T>;
+
+    fn neg(self) -> Point<T> {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// Wraps around a `width`×`height` area, negative coordinates included.
+impl Rem for Point {
+    type Output = Point;
+
+    fn rem(self, size: Point) -> Point {
+        Point::new(self.x.rem_euclid(size.x), self.y.rem_euclid(size.y))
+    }
+}
+
+impl Point {
+    pub const ZERO: Point = Point::new(0, 0);
+
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// Every point at a Manhattan distance of at most `radius`, including this one.
+    pub fn within_manhattan(self, radius: i64) -> impl Iterator<Item = Point> {
+        (-radius..=radius).flat_map(move |dx| {
+            let r = radius - dx.abs();
+            (-r..=r).map(move |dy| self + Point::new(dx, dy))
+        })
+    }
+}
+
+/// One of the four orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise, starting up.
+    pub const ALL: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+    pub fn turn_right(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn_left(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        self.turn_right().turn_right()
+    }
+
+    pub fn vector(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+}
+
+/// Parses the arrows used by the puzzles: `^`, `>`, `v` and `<`.
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' => Ok(Direction::Up),
+            '>' => Ok(Direction::Right),
+            'v' => Ok(Direction::Down),
+            '<' => Ok(Direction::Left),
+            _ => Err(format!("Invalid direction: {}", c)),
+        }
+    }
+}
+
+impl From<Direction> for char {
+    fn from(dir: Direction) -> char {
+        match dir {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", char::from(*self))
+    }
+}
+
+/// One of the eight directions around a cell, diagonals included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+impl Dir8 {
+    /// Clockwise, starting north.
+    pub const ALL: [Dir8; 8] = [Dir8::N, Dir8::NE, Dir8::E, Dir8::SE, Dir8::S, Dir8::SW, Dir8::W, Dir8::NW];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Rotates clockwise by 45°.
+    pub fn rotate_right(self) -> Dir8 {
+        Dir8::ALL[(self.index() + 1) % 8]
+    }
+
+    /// Rotates counter-clockwise by 45°.
+    pub fn rotate_left(self) -> Dir8 {
+        Dir8::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Dir8 {
+        Dir8::ALL[(self.index() + 4) % 8]
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn vector(self) -> Point {
+        match self {
+            Dir8::N => Point::new(0, -1),
+            Dir8::NE => Point::new(1, -1),
+            Dir8::E => Point::new(1, 0),
+            Dir8::SE => Point::new(1, 1),
+            Dir8::S => Point::new(0, 1),
+            Dir8::SW => Point::new(-1, 1),
+            Dir8::W => Point::new(-1, 0),
+            Dir8::NW => Point::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Dir8 {
+    fn from(dir: Direction) -> Dir8 {
+        match dir {
+            Direction::Up => Dir8::N,
+            Direction::Right => Dir8::E,
+            Direction::Down => Dir8::S,
+            Direction::Left => Dir8::W,
+        }
+    }
+}
+
+const ARROWS: [char; 8] = ['↑', '↗', '→', '↘', '↓', '↙', '←', '↖'];
+
+/// Parses the arrows `↑ ↗ → ↘ ↓ ↙ ← ↖`, as well as the orthogonal `^ > v <`.
+impl TryFrom<char> for Dir8 {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match ARROWS.iter().position(|&a| a == c) {
+            Some(i) => Ok(Dir8::ALL[i]),
+            None => Direction::try_from(c).map(Dir8::from),
+        }
+    }
+}
+
+impl From<Dir8> for char {
+    fn from(dir: Dir8) -> char {
+        ARROWS[dir.index()]
+    }
+}
+
+impl Display for Dir8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", char::from(*self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let p = Point::new(3, -2);
+
+        assert_eq!(p + Point::new(1, 1), Point::new(4, -1));
+        assert_eq!(p - Point::new(1, 1), Point::new(2, -3));
+        assert_eq!(p * 3, Point::new(9, -6));
+        assert_eq!(-p, Point::new(-3, 2));
+        assert_eq!(p % Point::new(2, 5), Point::new(1, 3));
+        assert_eq!(p.manhattan(Point::ZERO), 5);
+    }
+
+    #[test]
+    fn test_within_manhattan() {
+        let points: Vec<Point> = Point::ZERO.within_manhattan(2).collect();
+
+        assert_eq!(points.len(), 13);
+        assert!(points.iter().all(|p| p.manhattan(Point::ZERO) <= 2));
+    }
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction::try_from('v'), Ok(Direction::Down));
+        assert_eq!(Direction::Down.to_string(), "v");
+        assert!(Direction::try_from('x').is_err());
+        assert!(Direction::ALL.iter().all(|d| d.vector() + d.opposite().vector() == Point::ZERO));
+    }
+
+    #[test]
+    fn test_dir8() {
+        assert_eq!(Dir8::NW.rotate_right(), Dir8::N);
+        assert_eq!(Dir8::N.rotate_left(), Dir8::NW);
+        assert_eq!(Dir8::NE.opposite(), Dir8::SW);
+        assert_eq!(Dir8::try_from('↘'), Ok(Dir8::SE));
+        assert_eq!(Dir8::try_from('<'), Ok(Dir8::W));
+        assert_eq!(Dir8::E.to_string(), "→");
+        assert_eq!(Dir8::ALL.iter().filter(|d| d.is_diagonal()).count(), 4);
+        assert_eq!(Dir8::from(Direction::Right).vector(), Direction::Right.vector());
+    }
+}
@@ -0,0 +1,216 @@
+use std::{fmt::{self, Display}, str::FromStr};
+
+use crate::{error::ParseError, geometry::{Dir8, Direction, Point}};
+
+/// A rectangular grid stored row by row in a single vector.
+///
+/// Cells are addressed with signed coordinates so neighbours can be computed without
+/// worrying about underflow, anything outside the grid is simply `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid { width, height, cells: vec![fill; width * height] }
+    }
+
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Grid<T> {
+        assert_eq!(cells.len(), width * height, "a {}x{} grid needs {} cells", width, height, width * height);
+
+        Grid { width, height, cells }
+    }
+
+    /// Parses one row per line, turning each character into a cell with `f`.
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> Result<T, ParseError>) -> Result<Grid<T>, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        // blank lines around the picture are fine, a blank line inside it would hide a missing row
+        let lines: Vec<&str> = input.lines().map(str::trim_end).collect();
+        let first = lines.iter().position(|l| !l.is_empty()).unwrap_or(lines.len());
+        let last = lines.iter().rposition(|l| !l.is_empty()).map_or(first, |i| i + 1);
+
+        for &line in &lines[first..last] {
+            if line.is_empty() {
+                return Err(ParseError::new(format!("Row {} is blank", height + 1)).at(input, line).expected("grid cells"));
+            }
+
+            let before = cells.len();
+            for (i, c) in line.char_indices() {
+                cells.push(f(c).map_err(|e| e.at(input, &line[i..i + c.len_utf8()]))?);
+            }
+
+            let len = cells.len() - before;
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(ParseError::new(format!("Row {} has {} cells instead of {}", height + 1, len, width))
+                        .at(input, line)
+                        .expected(format!("{} cells", width)));
+                }
+                _ => {}
+            }
+            height += 1;
+        }
+
+        Ok(Grid { width: width.unwrap_or(0), height, cells })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, p: Point) -> bool {
+        p.x >= 0 && p.y >= 0 && (p.x as usize) < self.width && (p.y as usize) < self.height
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        self.in_bounds(p).then(|| p.y as usize * self.width + p.x as usize)
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.index(p).map(|i| &mut self.cells[i])
+    }
+
+    /// Stores `value` at `p`, returns false (and drops the value) when outside the grid.
+    pub fn set(&mut self, p: Point, value: T) -> bool {
+        match self.get_mut(p) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The orthogonal neighbours of `p` that lie inside the grid.
+    pub fn neighbours4(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL.into_iter().map(move |d| p + d.vector()).filter(|&n| self.in_bounds(n))
+    }
+
+    /// The orthogonal and diagonal neighbours of `p` that lie inside the grid.
+    pub fn neighbours8(&self, p: Point) -> impl Iterator<Item = Point> + '_ {
+        Dir8::ALL.into_iter().map(move |d| p + d.vector()).filter(|&n| self.in_bounds(n))
+    }
+
+    /// Every coordinate of the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Point> {
+        let (width, height) = (self.width as i64, self.height as i64);
+
+        (0..height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Every cell with its coordinates, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// The first position, row by row, holding `value`.
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.iter().find(|(_, cell)| *cell == value).map(|(pos, _)| pos)
+    }
+
+    /// A grid of the same size with every cell transformed by `f`.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { width: self.width, height: self.height, cells: self.cells.iter().map(f).collect() }
+    }
+}
+
+impl<T: TryFrom<char>> FromStr for Grid<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse(s, |c| T::try_from(c).map_err(|_| ParseError::new(format!("Unexpected character '{}'", c))))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let grid: Grid<char> = "ab\ncd\nef\n".parse().unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.get(Point::new(1, 2)), Some(&'f'));
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.find(&'d'), Some(Point::new(1, 1)));
+        assert_eq!(grid.to_string(), "ab\ncd\nef\n");
+
+        assert!("ab\nc".parse::<Grid<char>>().is_err());
+        let error = Grid::parse("..\na#", |c| if c == '#' { Err(ParseError::new("no walls")) } else { Ok(c) }).unwrap_err();
+        assert_eq!(error.location.map(|l| (l.line, l.column)), Some((2, 2)));
+
+        let grid: Grid<char> = "\n\nab\ncd\n\n\n".parse().unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        let error = "ab\n\ncd\n".parse::<Grid<char>>().unwrap_err();
+        assert_eq!(error.location.map(|l| l.line), Some(2));
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::from_vec(3, 2, vec![1, 2, 3, 4, 5, 6]);
+
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.columns().count(), 3);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let mut grid = Grid::new(3, 3, 0);
+
+        assert!(grid.set(Point::new(2, 2), 7));
+        assert!(!grid.set(Point::new(3, 0), 7));
+        assert_eq!(grid.neighbours4(Point::ZERO).collect::<Vec<_>>(), [Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbours8(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbours8(Point::new(2, 2)).filter(|&n| grid.get(n) == Some(&0)).count(), 3);
+    }
+}
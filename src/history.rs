@@ -0,0 +1,231 @@
+use std::{fs::{self, OpenOptions}, io::Write, path::{Path, PathBuf}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+
+use crate::bench::{BenchReport, Stats};
+
+const HISTORY_PATH: &str = "bench_history.jsonl";
+
+/// A benchmark run as stored in the history, one JSON object per line.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub commit: Option<String>,
+    pub timestamp: u64,
+    pub report: BenchReport,
+}
+
+impl Entry {
+    pub fn new(report: BenchReport) -> Entry {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+
+        Entry { commit: git_commit(Path::new(".git")), timestamp, report }
+    }
+
+    fn label(&self) -> String {
+        match &self.commit {
+            Some(commit) => format!("commit {}", &commit[..commit.len().min(10)]),
+            None => format!("run at {}", self.timestamp),
+        }
+    }
+}
+
+pub fn history_path() -> PathBuf {
+    PathBuf::from(HISTORY_PATH)
+}
+
+pub fn append(entry: &Entry) -> Result<(), String> {
+    let path = history_path();
+    let line = serde_json::to_string(entry).map_err(|e| format!("Could not serialize benchmark: {}", e))?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Could not open {}: {}", path.display(), e))?;
+
+    writeln!(file, "{}", line).map_err(|e| format!("Could not write {}: {}", path.display(), e))
+}
+
+pub fn load() -> Result<Vec<Entry>, String> {
+    let path = history_path();
+    let content = fs::read_to_string(&path).map_err(|e| format!("Could not read benchmark history {}: {}", path.display(), e))?;
+
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| serde_json::from_str(line).map_err(|e| format!("Invalid entry at {}:{}: {}", path.display(), i + 1, e)))
+        .collect()
+}
+
+/// Reads the commit `HEAD` points to without calling git, following a branch through loose or packed refs.
+fn git_commit(git_dir: &Path) -> Option<String> {
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+
+    let Some(reference) = head.strip_prefix("ref: ") else {
+        return Some(head.to_string());
+    };
+
+    if let Ok(commit) = fs::read_to_string(git_dir.join(reference)) {
+        return Some(commit.trim().to_string());
+    }
+
+    fs::read_to_string(git_dir.join("packed-refs"))
+        .ok()?
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .find(|&(_, name)| name == reference)
+        .map(|(commit, _)| commit.to_string())
+}
+
+/// A single measurement compared between two runs, on the median.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub day: usize,
+    pub what: String,
+    pub baseline_ns: u64,
+    pub latest_ns: u64,
+}
+
+impl Change {
+    /// Relative change in percent, positive when slower.
+    pub fn percent(&self) -> f64 {
+        (self.latest_ns as f64 - self.baseline_ns as f64) / self.baseline_ns.max(1) as f64 * 100.0
+    }
+}
+
+/// Pairs up the measurements present in both runs.
+pub fn changes(baseline: &BenchReport, latest: &BenchReport) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    for day in &latest.days {
+        let Some(base) = baseline.days.iter().find(|d| d.day == day.day) else {
+            continue;
+        };
+
+        let change = |what: String, base: &Stats, latest: &Stats| Change {
+            day: day.day,
+            what,
+            baseline_ns: base.median_ns,
+            latest_ns: latest.median_ns,
+        };
+
+        changes.push(change("parse".to_string(), &base.parse, &day.parse));
+        for part in &day.parts {
+            if let Some(base) = base.parts.iter().find(|p| p.part == part.part) {
+                changes.push(change(format!("part {}", part.part), &base.stats, &part.stats));
+            }
+        }
+    }
+
+    changes
+}
+
+/// Picks the latest run and the baseline: the newest earlier run on a matching commit, or simply the previous one.
+pub fn select<'a>(entries: &'a [Entry], baseline: Option<&str>) -> Result<(&'a Entry, &'a Entry), String> {
+    let Some((latest, earlier)) = entries.split_last() else {
+        return Err("The benchmark history is empty, run `bench` first".to_string());
+    };
+
+    let base = match baseline {
+        Some(prefix) => earlier
+            .iter()
+            .rev()
+            .find(|e| e.commit.as_deref().is_some_and(|c| c.starts_with(prefix)))
+            .ok_or_else(|| format!("No earlier benchmark run for commit {}", prefix))?,
+        None => earlier.last().ok_or("Only one benchmark run in the history, nothing to compare")?,
+    };
+
+    Ok((base, latest))
+}
+
+/// Prints the comparison and returns whether nothing regressed past `threshold` percent.
+pub fn print_comparison(baseline: &Entry, latest: &Entry, threshold: f64) -> bool {
+    println!("Comparing {} against {} (median, threshold {}%)\n", latest.label(), baseline.label(), threshold);
+
+    let changes = changes(&baseline.report, &latest.report);
+    if changes.is_empty() {
+        println!("{}", "No day was measured in both runs".yellow());
+        return true;
+    }
+
+    let mut regressions = 0;
+    for change in &changes {
+        let percent = change.percent();
+        let line = format!(
+            "Day {:0>2} {:<7} {:>10.2?} → {:>10.2?} ({:+.1}%)",
+            change.day,
+            change.what,
+            Duration::from_nanos(change.baseline_ns),
+            Duration::from_nanos(change.latest_ns),
+            percent,
+        );
+
+        if percent > threshold {
+            regressions += 1;
+            println!("{} {}", line.red(), "regressed".red().bold());
+        } else if percent < -threshold {
+            println!("{}", line.green());
+        } else {
+            println!("{}", line.dimmed());
+        }
+    }
+
+    println!();
+    if regressions > 0 {
+        println!("{}", format!("{} measurement(s) regressed by more than {}%", regressions, threshold).red());
+    }
+
+    regressions == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{DayBench, PartBench};
+
+    fn stats(median_ns: u64) -> Stats {
+        Stats { min_ns: median_ns, median_ns, mean_ns: median_ns, stddev_ns: 0 }
+    }
+
+    fn report(days: &[(usize, u64, u64)]) -> BenchReport {
+        BenchReport {
+            warmup: 0,
+            iterations: 1,
+            days: days
+                .iter()
+                .map(|&(day, parse, part1)| DayBench { day, parse: stats(parse), parts: vec![PartBench { part: 1, stats: stats(part1) }] })
+                .collect(),
+        }
+    }
+
+    fn entry(commit: &str, report: BenchReport) -> Entry {
+        Entry { commit: Some(commit.to_string()), timestamp: 0, report }
+    }
+
+    #[test]
+    fn test_changes() {
+        let changes = changes(&report(&[(1, 100, 1000), (2, 50, 50)]), &report(&[(1, 100, 1500), (3, 10, 10)]));
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].percent(), 0.0);
+        assert_eq!(changes[1], Change { day: 1, what: "part 1".to_string(), baseline_ns: 1000, latest_ns: 1500 });
+        assert_eq!(changes[1].percent(), 50.0);
+    }
+
+    #[test]
+    fn test_select() {
+        let entries = vec![entry("abc123", report(&[])), entry("def456", report(&[])), entry("789fed", report(&[]))];
+
+        let (base, latest) = select(&entries, None).unwrap();
+        assert_eq!((base.commit.as_deref(), latest.commit.as_deref()), (Some("def456"), Some("789fed")));
+
+        let (base, _) = select(&entries, Some("abc")).unwrap();
+        assert_eq!(base.commit.as_deref(), Some("abc123"));
+
+        assert!(select(&entries, Some("789")).is_err());
+        assert!(select(&entries[..1], None).is_err());
+    }
+}
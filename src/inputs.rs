@@ -0,0 +1,132 @@
+use std::{env, fs, io::{self, Read}, path::{Path, PathBuf}};
+
+use crate::config::Config;
+
+/// Environment variable naming the directory inputs are read from, it wins over `aoc.toml`.
+pub const INPUTS_ENV: &str = "AOC_INPUTS";
+
+/// Environment variable naming the user whose inputs are used, it wins over `aoc.toml`.
+pub const USER_ENV: &str = "AOC_USER";
+
+/// Where a day's input comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    /// A file given explicitly.
+    File(PathBuf),
+    /// A file found in the inputs directory, `name` is set for one of several inputs of a day
+    /// (`inputs/d07/alice.txt`).
+    Discovered { path: PathBuf, name: Option<String> },
+}
+
+impl Source {
+    /// The source given with `--input`, `-` reads stdin.
+    pub fn from_arg(path: &Path) -> Source {
+        match path.to_str() {
+            Some("-") => Source::Stdin,
+            _ => Source::File(path.to_path_buf()),
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Source::Discovered { name, .. } => name.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn read(&self) -> Result<String, String> {
+        match self {
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input).map_err(|e| format!("Could not read input from stdin: {}", e))?;
+                Ok(input)
+            }
+            Source::File(path) | Source::Discovered { path, .. } => {
+                fs::read_to_string(path).map_err(|e| format!("Could not read input file {}: {}", path.display(), e))
+            }
+        }
+    }
+}
+
+/// The directory inputs are looked up in: `AOC_INPUTS`, then `inputs` from `aoc.toml`, then `inputs`,
+/// with the user's subfolder when one is set.
+pub fn input_dir(config: &Config) -> PathBuf {
+    let dir = env::var_os(INPUTS_ENV)
+        .map(PathBuf::from)
+        .or_else(|| config.inputs.clone())
+        .unwrap_or_else(|| PathBuf::from("inputs"));
+
+    match env::var(USER_ENV).ok().or_else(|| config.user.clone()).filter(|user| !user.is_empty()) {
+        Some(user) => dir.join(user),
+        None => dir,
+    }
+}
+
+/// Finds the inputs of a day in `dir`: every `dNN/*.txt` sorted by name when that directory
+/// exists, otherwise the single `dNN.txt`.
+pub fn discover(dir: &Path, day: usize) -> Result<Vec<Source>, String> {
+    let folder = dir.join(format!("d{:0>2}", day));
+    let file = dir.join(format!("d{:0>2}.txt", day));
+
+    if folder.is_dir() {
+        let entries = fs::read_dir(&folder).map_err(|e| format!("Could not read input directory {}: {}", folder.display(), e))?;
+
+        let mut paths = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| format!("Could not read input directory {}: {}", folder.display(), e))?.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        if paths.is_empty() {
+            return Err(format!("No input files in {}, expected *.txt", folder.display()));
+        }
+
+        return Ok(paths.into_iter().map(|path| {
+            let name = path.file_stem().map(|stem| stem.to_string_lossy().into_owned());
+            Source::Discovered { path, name }
+        }).collect());
+    }
+
+    if file.is_file() {
+        return Ok(vec![Source::Discovered { path: file, name: None }]);
+    }
+
+    Err(format!("No input found, tried {} and {}", file.display(), folder.join("*.txt").display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover() {
+        let dir = env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        fs::create_dir_all(dir.join("d07")).unwrap();
+        fs::write(dir.join("d03.txt"), "3").unwrap();
+        fs::write(dir.join("d07/bob.txt"), "b").unwrap();
+        fs::write(dir.join("d07/alice.txt"), "a").unwrap();
+        fs::write(dir.join("d07/notes.md"), "").unwrap();
+
+        let single = discover(&dir, 3).unwrap();
+        assert_eq!(single, [Source::Discovered { path: dir.join("d03.txt"), name: None }]);
+        assert_eq!(single[0].read(), Ok("3".to_string()));
+
+        let names: Vec<_> = discover(&dir, 7).unwrap().iter().map(|s| s.name().map(str::to_string)).collect();
+        assert_eq!(names, [Some("alice".to_string()), Some("bob".to_string())]);
+
+        let error = discover(&dir, 9).unwrap_err();
+        assert!(error.contains(&dir.join("d09.txt").display().to_string()));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Source::from_arg(Path::new("-")), Source::Stdin);
+        assert_eq!(Source::from_arg(Path::new("a.txt")), Source::File(PathBuf::from("a.txt")));
+    }
+}
@@ -0,0 +1,61 @@
+//! Diagnostics go through `tracing` to stderr, so stdout keeps only the progress and the answers.
+
+use std::io::{self, IsTerminal};
+
+use tracing_subscriber::EnvFilter;
+
+use crate::cli::DaySet;
+
+/// Environment variable with filter directives replacing the ones from the flags,
+/// like `advent_of_code_24::d17=trace`.
+pub const LOG_ENV: &str = "AOC_LOG";
+
+const CRATE: &str = env!("CARGO_CRATE_NAME");
+
+/// The filter for `-v` given `verbose` times: info by default, then debug and trace. With `days`
+/// the extra levels only apply to those days, everything else stays at info.
+fn directives(verbose: u8, days: Option<&DaySet>) -> String {
+    let level = match verbose {
+        0 => "info",
+        1 => "debug",
+        _ => "trace",
+    };
+
+    // dependencies only get to warn, their debug output is not what -v is for
+    let mut directives = vec!["warn".to_string()];
+    match days {
+        Some(days) => {
+            directives.push(format!("{}=info", CRATE));
+            directives.extend(days.0.iter().map(|day| format!("{}::d{:0>2}={}", CRATE, day, level)));
+        }
+        None => directives.push(format!("{}={}", CRATE, level)),
+    }
+
+    directives.join(",")
+}
+
+pub fn init(verbose: u8, days: Option<&DaySet>) {
+    let filter = EnvFilter::try_from_env(LOG_ENV).unwrap_or_else(|_| EnvFilter::new(directives(verbose, days)));
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .without_time()
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directives() {
+        assert_eq!(directives(0, None), "warn,advent_of_code_24=info");
+        assert_eq!(directives(3, None), "warn,advent_of_code_24=trace");
+        assert_eq!(
+            directives(1, Some(&"15,22".parse().unwrap())),
+            "warn,advent_of_code_24=info,advent_of_code_24::d15=debug,advent_of_code_24::d22=debug"
+        );
+    }
+}
@@ -1,8 +1,44 @@
-use std::{fmt::{self, Display}, fs, sync::LazyLock, time::Instant};
+use std::{fmt::{self, Display}, fs, path::{Path, PathBuf}, process::ExitCode, sync::{Arc, LazyLock}, time::{Duration, Instant}};
 
+use answers::{Answers, Verdict};
+use artifacts::{Scope, Store};
+use bench::BenchReport;
+use clap::Parser;
+use cli::{BenchArgs, Cli, Command, CompareArgs, DayArgs, Format, RunArgs, Selection};
 use colored::Colorize;
+use config::Config;
+use context::{Context, Output, Verbosity};
+use error::ParseError;
+use examples::Example;
+use fetch::Http;
+use inputs::Source;
+use memory::Measure;
+use params::Params;
+use report::{DayResult, PartResult};
+use solution::{ParseFn, Parsed};
+use worker::Failure;
 
-type Part = fn(&String) -> Box<dyn ToString>;
+mod answers;
+mod artifacts;
+mod bench;
+mod circuit;
+mod cli;
+mod config;
+mod context;
+mod error;
+mod examples;
+mod fetch;
+mod geometry;
+mod grid;
+mod history;
+mod inputs;
+mod logging;
+mod memory;
+mod params;
+mod report;
+mod search;
+mod solution;
+mod worker;
 
 #[macro_use]
 mod days;
@@ -11,8 +47,69 @@ days!(d01, d02, d03, d04, d05, d06, d07, d08, d09, d10, d11, d12, d13, d14, d15,
 #[derive(Clone)]
 struct Day {
     num: usize,
-    part1: Part,
-    part2: Part,
+    parse: ParseFn,
+    params: &'static [(&'static str, &'static str)],
+    examples: &'static [Example],
+}
+
+/// Why a day could not be run on its inputs.
+#[derive(Debug)]
+enum InputError {
+    /// The day has no input and there is no way to download it, running several days skips it.
+    Missing(String),
+    /// The input is there but could not be downloaded or read.
+    Failed(String),
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Missing(e) | InputError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl From<InputError> for String {
+    fn from(e: InputError) -> String {
+        e.to_string()
+    }
+}
+
+/// A part running longer than this fails unless `--timeout` or `aoc.toml` say otherwise.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How the days are run.
+struct RunOptions {
+    /// Leaves out the progress so stdout only gets the final results.
+    quiet: bool,
+    /// How long a part may take, `None` waits forever.
+    timeout: Option<Duration>,
+    headless: bool,
+    /// Where the parts' files are kept, `None` keeps none.
+    artifacts: Option<Arc<Store>>,
+}
+
+impl RunOptions {
+    /// The options for the given arguments, the timeout falls back to `aoc.toml` and then the default.
+    fn new(args: &RunArgs, quiet: bool, config: &Config) -> RunOptions {
+        let timeout = match args.timeout.or(config.timeout) {
+            Some(0) => None,
+            Some(seconds) => Some(Duration::from_secs(seconds)),
+            None => Some(DEFAULT_TIMEOUT),
+        };
+        // a headless run only writes files when asked to
+        let artifacts = args.artifacts.clone().or_else(|| (!args.headless).then(|| PathBuf::from("output"))).map(|root| Arc::new(Store::new(&root)));
+
+        RunOptions { quiet, timeout, headless: args.headless, artifacts }
+    }
+
+    /// The context handed to a part, its output goes to stderr when stdout is kept for the results.
+    fn context(&self, scope: Scope) -> Context {
+        let output = if self.quiet { Output::Stderr } else { Output::Stdout };
+        let verbosity = if self.headless { Verbosity::Quiet } else { Verbosity::Normal };
+
+        Context::new(output, self.artifacts.clone().map(|store| (store, scope)), verbosity, self.headless)
+    }
 }
 
 impl Display for Day {
@@ -21,34 +118,400 @@ impl Display for Day {
     }
 }
 
-fn run_day(day: &Day) {
-    if let Ok(input) = fs::read_to_string(format!("inputs/d{:0>2}.txt", day.num)) {
-        println!("Running {}...\n", day);
+impl Day {
+    /// The inputs to run the day on: the one given with `--input`, or those in the inputs directory,
+    /// where a missing input is downloaded when a session token is set.
+    fn inputs(&self, path: Option<&Path>, config: &Config) -> Result<Vec<Source>, InputError> {
+        if let Some(path) = path {
+            return Ok(vec![Source::from_arg(path)]);
+        }
+
+        let dir = inputs::input_dir(config);
+        let error = match inputs::discover(&dir, self.num) {
+            Ok(sources) => return Ok(sources),
+            Err(e) => format!("{}: {}", self, e),
+        };
+
+        let Some(http) = Http::from_config(config) else {
+            return Err(InputError::Missing(format!("{}, set {} to download it", error, fetch::SESSION_ENV)));
+        };
+
+        tracing::info!("Downloading the input of {}...", self);
+        let path = fetch::Cached::new(&dir, http).get(self.num).map_err(|e| InputError::Failed(format!("{}: {}", self, e)))?;
+
+        Ok(vec![Source::Discovered { path, name: None }])
+    }
+
+    fn parts(only: Option<u8>) -> Vec<u8> {
+        [1, 2].into_iter().filter(|&n| only.is_none_or(|only| only == n)).collect()
+    }
+
+    /// The day's parameters: its defaults, then `aoc.toml`, then the `--param` values it declares.
+    fn params(&self, config: &Config, overrides: &[(String, String)]) -> Result<Params, String> {
+        let mut params = Params::new(self.params);
+
+        for (name, value) in config.params(self.num) {
+            params.set(&name, &value).map_err(|e| format!("{} for {} in {}", e, self, Config::path().display()))?;
+        }
+        for (name, value) in overrides {
+            if params.contains(name) {
+                params.set(name, value)?;
+            }
+        }
+
+        Ok(params)
+    }
+}
+
+/// Runs the parts of a day.
+fn run_day(day: &Day, part: Option<u8>, source: &Source, params: &Params, options: &RunOptions) -> Result<DayResult, String> {
+    let input = source.read()?;
+    let name = source.name();
+
+    // the stored answers only hold for the inputs in the inputs directory
+    let answers = match source {
+        Source::Discovered { .. } => Answers::load(day.num, name).unwrap_or_else(|e| {
+            eprintln!("{}\n", e.yellow());
+            Answers::default()
+        }),
+        _ => Answers::default(),
+    };
+
+    Ok(solve(day, name, &Day::parts(part), &input, params, &answers, options))
+}
+
+/// Runs a day on each of its inputs one after another, adding to `results`. It stops at the
+/// first input that cannot be read, keeping the results of the inputs before it.
+fn run_inputs(day: &Day, part: Option<u8>, path: Option<&Path>, config: &Config, params: &Params, options: &RunOptions, results: &mut Vec<DayResult>) -> Result<(), InputError> {
+    for source in day.inputs(path, config)? {
+        results.push(run_day(day, part, &source, params, options).map_err(InputError::Failed)?);
+    }
+
+    Ok(())
+}
+
+/// Runs a day against each of its examples that has an answer for the selected parts,
+/// the examples' own parameters replace the ones in `params`.
+fn run_examples(day: &Day, part: Option<u8>, params: &Params, options: &RunOptions) -> Vec<DayResult> {
+    let mut results = Vec::new();
+
+    for (i, example) in day.examples.iter().enumerate() {
+        let name = format!("example {}", i + 1);
+        let parts: Vec<u8> = example.parts().into_iter().filter(|&n| part.is_none_or(|p| p == n)).collect();
+        if parts.is_empty() {
+            continue;
+        }
+
+        let params = match example.apply(params.clone()) {
+            Ok(params) => params,
+            Err(e) => {
+                let e = ParseError::new(e).for_day(day.num);
+                if !options.quiet {
+                    println!("» {} {}\n", "Invalid example:".red(), e.render());
+                }
+                results.push(DayResult { day: day.num, input: Some(name), parse_time: Duration::ZERO, outcome: Err(e) });
+                continue;
+            }
+        };
+
+        results.push(solve(day, Some(&name), &parts, example.input, &params, &example.answers(), options));
+    }
+
+    results
+}
+
+/// Runs the parts of a day on `input`, `name` tells it apart from the day's other inputs.
+fn solve(day: &Day, name: Option<&str>, parts: &[u8], input: &str, params: &Params, answers: &Answers, options: &RunOptions) -> DayResult {
+    let quiet = options.quiet;
+    if !quiet {
+        match name {
+            Some(name) => println!("Running {} ({})...\n", day, name),
+            None => println!("Running {}...\n", day),
+        }
+        if !params.is_empty() {
+            println!("» Parameters: {}\n", params.to_string().dimmed());
+        }
+    }
+
+    // parsing and each part run on worker threads so a panic or a part running away only fails that step
+    let (parse, input_copy, params_copy) = (day.parse, input.to_string(), params.clone());
+    let start = Instant::now();
+    let parsed = worker::run(format!("d{:0>2} parse", day.num), options.timeout, move || {
         let start = Instant::now();
-        let result = (day.part1)(&input);
-        let duration = start.elapsed();
-        println!("» Part 1: {} {}\n", result.to_string().green(), format!("(took {:?})", duration).dimmed());
-    
+        (parse(&input_copy, &params_copy), start.elapsed())
+    });
+    let (parsed, parse_time) = parsed.unwrap_or_else(|failure| {
+        if let Failure::Timeout(_) = failure {
+            memory::abandon();
+        }
+        (Err(ParseError::new(format!("The parser {}", failure))), start.elapsed())
+    });
+
+    let parsed: Arc<dyn Parsed> = match parsed {
+        Ok(parsed) => parsed.into(),
+        Err(e) => {
+            let e = e.for_day(day.num);
+            if !quiet {
+                println!("» {} {}\n", "Parse error:".red(), e.render());
+            }
+            return DayResult { day: day.num, input: name.map(str::to_string), parse_time, outcome: Err(e) };
+        }
+    };
+    if !quiet {
+        println!("» Parsed {}\n", format!("(took {:?})", parse_time).dimmed());
+    }
+
+    let mut results = Vec::new();
+    for &n in parts {
+        let context = Arc::new(options.context(Scope { day: day.num, input: name.map(str::to_string), part: n }));
+        let (shared, part_context) = (Arc::clone(&parsed), Arc::clone(&context));
         let start = Instant::now();
-        let result = (day.part2)(&input);
-        let duration = start.elapsed();
-        println!("» Part 2: {} {}\n", result.to_string().green(), format!("(took {:?})", duration).dimmed());
+        let outcome = worker::run(format!("d{:0>2} part {}", day.num, n), options.timeout, move || {
+            let measure = Measure::start();
+            let start = Instant::now();
+            let answer = shared.solve(n, &part_context);
+            (answer, start.elapsed(), measure.finish())
+        });
+
+        let (answer, duration, memory) = match outcome {
+            Ok((answer, duration, memory)) => (Ok(answer), duration, memory),
+            Err(failure) => {
+                // a worker that timed out is left running, a part that checks its context stops early
+                if let Failure::Timeout(_) = failure {
+                    context.cancel();
+                    memory::abandon();
+                }
+                (Err(failure), start.elapsed(), None)
+            }
+        };
+        let verdict = match &answer {
+            Ok(answer) => answers.check(n, answer),
+            Err(_) => Verdict::Unknown,
+        };
+        if !quiet {
+            let took = match memory {
+                Some(usage) => format!("(took {:?}, peak {}, {} allocations)", duration, memory::format_bytes(usage.peak), usage.allocations),
+                None => format!("(took {:?})", duration),
+            }.dimmed();
+            match &answer {
+                Ok(answer) => println!("» Part {}: {} [{}] {}", n, answer.to_string().green(), verdict.colored(), took),
+                Err(failure) => println!("» Part {}: {} {}", n, failure.to_string().red(), took),
+            }
+            for artifact in context.produced() {
+                println!("  {} {}", artifact.path.display(), format!("({}, {})", artifact.kind, memory::format_bytes(artifact.bytes as usize)).dimmed());
+            }
+            println!();
+        }
+
+        results.push(PartResult { part: n, answer, verdict, duration, memory });
+    }
+    if !quiet {
         println!();
-    } else {
-        println!("{}", "Could not read input file".red());
     }
+
+    DayResult { day: day.num, input: name.map(str::to_string), parse_time, outcome: Ok(results) }
 }
 
-fn main() {
+/// Resolves a day selection to the implemented days, or an error naming the missing ones.
+fn select_days(selection: &Selection) -> Result<Vec<&'static Day>, String> {
+    if selection.all {
+        return Ok(DAYS.iter().collect());
+    }
+
+    let Some(set) = &selection.days else {
+        return Err("No day selected, pass a day number or --all".to_string());
+    };
+
+    let missing: Vec<String> = set.0.iter()
+        .filter(|&&num| !DAYS.iter().any(|d| d.num == num))
+        .map(|num| num.to_string())
+        .collect();
 
-    // if there is a command line argument, run that day directly and exit
-    if let Some(day) = std::env::args().nth(1) {
-        let day = day.parse::<usize>().unwrap();
-        let day = DAYS.iter().find(|d| d.num == day).unwrap();
-        run_day(day);
-        return;
+    if !missing.is_empty() {
+        return Err(format!("Not implemented: day {}", missing.join(", ")));
     }
 
+    Ok(DAYS.iter().filter(|d| set.0.contains(&d.num)).collect())
+}
+
+fn select_days_with_input(args: &DayArgs) -> Result<(Vec<&'static Day>, Option<&Path>), String> {
+    let days = select_days(&args.selection)?;
+
+    if args.input.is_some() && days.len() != 1 {
+        return Err("--input can only be used with a single day".to_string());
+    }
+
+    // a parameter only has to exist for one of the days, the others ignore it
+    for (name, _) in &args.params {
+        if !days.iter().any(|d| d.params.iter().any(|(n, _)| n == name)) {
+            return Err(format!("Unknown parameter {} for the selected days", name));
+        }
+    }
+
+    Ok((days, args.input.as_deref()))
+}
+
+fn run(args: &RunArgs) -> Result<bool, String> {
+    let (days, path) = select_days_with_input(&args.days)?;
+    let format = args.format.or(args.output.as_ref().map(|_| Format::Plain));
+    let config = Config::load()?;
+    let options = RunOptions::new(args, format.is_some(), &config);
+
+    if args.example {
+        let mut results = Vec::new();
+        for day in &days {
+            results.extend(run_examples(day, args.days.part, &day.params(&config, &args.days.params)?, &options));
+        }
+        print_results(args, format, &results, &[])?;
+
+        return Ok(results.iter().all(DayResult::passed));
+    }
+
+    if let ([day], None) = (&days[..], format) {
+        let params = day.params(&config, &args.days.params)?;
+        let mut results = Vec::new();
+        let outcome = run_inputs(day, args.days.part, path, &config, &params, &options, &mut results);
+        if results.len() > 1 {
+            report::print_summary(&results, &[]);
+        }
+        if let Err(e) = outcome {
+            println!("{}", e.to_string().red());
+            return Ok(false);
+        }
+
+        return Ok(results.iter().all(DayResult::passed));
+    }
+
+    // when running several days, those without an input are skipped and listed in the summary,
+    // other errors are told on stderr so they stay out of the results
+    let single = days.len() == 1;
+    let mut results = Vec::new();
+    let mut skipped = Vec::new();
+    let mut failed = false;
+
+    for day in days {
+        let params = day.params(&config, &args.days.params)?;
+        match run_inputs(day, args.days.part, path, &config, &params, &options, &mut results) {
+            Ok(()) => {}
+            Err(InputError::Missing(_)) if !single => skipped.push(day),
+            Err(e) => {
+                eprintln!("{}", e.to_string().red());
+                failed = true;
+            }
+        }
+    }
+
+    print_results(args, format, &results, &skipped)?;
+
+    Ok(!failed && results.iter().all(DayResult::passed))
+}
+
+fn print_results(args: &RunArgs, format: Option<Format>, results: &[DayResult], skipped: &[&Day]) -> Result<(), String> {
+    match format {
+        Some(format) => {
+            let rendered = report::render(format, results, skipped)?;
+            match &args.output {
+                Some(output) => fs::write(output, rendered).map_err(|e| format!("Could not write {}: {}", output.display(), e))?,
+                None => print!("{}", rendered),
+            }
+        }
+        None => report::print_summary(results, skipped),
+    }
+
+    Ok(())
+}
+
+fn bench(args: &BenchArgs) -> Result<bool, String> {
+    if args.iterations == 0 {
+        return Err("--iterations must be at least 1".to_string());
+    }
+
+    let (days, path) = select_days_with_input(&args.days)?;
+    let config = Config::load()?;
+
+    let mut results = Vec::new();
+    let mut failed = 0;
+    for day in days {
+        let params = day.params(&config, &args.days.params)?;
+        // a day with several inputs is measured on the first one
+        let result = day.inputs(path, &config)
+            .map_err(String::from)
+            .and_then(|sources| bench::bench_day(day, args.days.part, &sources[0], &params, args.warmup, args.iterations));
+        match result {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                println!("{}\n", e.red());
+                failed += 1;
+            }
+        }
+    }
+
+    let report = BenchReport { warmup: args.warmup, iterations: args.iterations, days: results };
+
+    if let Some(json) = &args.json {
+        report.write_json(json)?;
+        println!("Results written to {}", json.display());
+    }
+
+    // the history compares runs on the puzzle inputs, timings on another input would not compare
+    if path.is_some() {
+        println!("Run not recorded in {}, it used another input", history::history_path().display());
+    } else if !args.no_history && !report.days.is_empty() {
+        history::append(&history::Entry::new(report))?;
+        println!("Run recorded in {}", history::history_path().display());
+    }
+
+    Ok(failed == 0)
+}
+
+fn compare(args: &CompareArgs) -> Result<bool, String> {
+    let entries = history::load()?;
+    let (baseline, latest) = history::select(&entries, args.baseline.as_deref())?;
+
+    Ok(history::print_comparison(baseline, latest, args.threshold))
+}
+
+fn list() -> Result<bool, String> {
+    let config = Config::load()?;
+
+    for day in DAYS.iter() {
+        let input = match inputs::discover(&inputs::input_dir(&config), day.num).map(|sources| sources.len()) {
+            Ok(1) => "input available".green(),
+            Ok(n) => format!("{} inputs", n).green(),
+            Err(_) => "no input".dimmed(),
+        };
+        let examples = match day.examples.len() {
+            1 => "1 example".to_string(),
+            n => format!("{} examples", n),
+        };
+        println!("{} ({}, {})", day, input, examples);
+    }
+
+    Ok(true)
+}
+
+/// Checks the selected days against their examples, only printing the summary.
+fn test_examples(selection: &Selection) -> Result<bool, String> {
+    let days = select_days(selection)?;
+    let config = Config::load()?;
+
+    let mut results = Vec::new();
+    for day in days {
+        results.extend(run_examples(day, None, &day.params(&config, &[])?, &RunOptions::new(&RunArgs::default(), true, &config)));
+    }
+
+    report::print_summary(&results, &[]);
+
+    Ok(results.iter().all(DayResult::passed))
+}
+
+fn interactive() {
+    let config = Config::load().unwrap_or_else(|e| {
+        eprintln!("{}\n", e.yellow());
+        Config::default()
+    });
+    let options = RunOptions::new(&RunArgs::default(), false, &config);
+
     loop {
         let day = inquire::Select::new("Choose the day to run", DAYS.clone()).prompt();
         if day.is_err() {
@@ -56,6 +519,36 @@ fn main() {
             break;
         }
         let day = day.unwrap();
-        run_day(&day);
+        let result = day.params(&config, &[]).and_then(|params| Ok(run_inputs(&day, None, None, &config, &params, &options, &mut Vec::new())?));
+        if let Err(e) = result {
+            println!("{}", e.red());
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    logging::init(cli.verbose, cli.log_days.as_ref());
+
+    let result = match &cli.command {
+        Some(Command::Run(args)) => run(args),
+        Some(Command::List) => list(),
+        Some(Command::Bench(args)) => bench(args),
+        Some(Command::Compare(args)) => compare(args),
+        Some(Command::TestExamples(selection)) => test_examples(selection),
+        None if cli.run.days.selection.is_empty() && cli.run.days.part.is_none() && cli.run.days.input.is_none() && !cli.run.example => {
+            interactive();
+            Ok(true)
+        }
+        None => run(&cli.run),
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            ExitCode::from(2)
+        }
     }
 }
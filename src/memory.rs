@@ -0,0 +1,155 @@
+//! Heap usage of the solutions, counted by a global allocator when the `memory` feature is enabled.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use serde::Serialize;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+/// Set once a part is left running after timing out, its allocations end up in whatever is measured next.
+static ABANDONED: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "memory")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    use super::*;
+
+    /// The system allocator, keeping count of what goes through it.
+    struct Counting;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    fn allocated(size: usize) {
+        let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        TOTAL.fetch_add(size, Ordering::Relaxed);
+        COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+                allocated(new_size);
+            }
+            new
+        }
+    }
+}
+
+/// What a part cost in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Usage {
+    /// The most bytes the part held at once, on top of what was in use when it started.
+    pub peak: usize,
+    /// Bytes allocated over the whole part, including the ones freed again.
+    pub allocated: usize,
+    pub allocations: usize,
+}
+
+/// Heap usage being measured, the counters are shared by all threads so one thing is measured at a time.
+/// A part's own helper threads count towards it, but so would a part left running after a timeout.
+pub struct Measure {
+    current: usize,
+    total: usize,
+    count: usize,
+}
+
+impl Measure {
+    pub fn start() -> Measure {
+        let current = CURRENT.load(Ordering::Relaxed);
+        PEAK.store(current, Ordering::Relaxed);
+
+        Measure { current, total: TOTAL.load(Ordering::Relaxed), count: COUNT.load(Ordering::Relaxed) }
+    }
+
+    /// The usage since the start, nothing is known without the `memory` feature or once a part
+    /// was abandoned.
+    pub fn finish(self) -> Option<Usage> {
+        (cfg!(feature = "memory") && !ABANDONED.load(Ordering::Relaxed)).then(|| Usage {
+            peak: PEAK.load(Ordering::Relaxed).saturating_sub(self.current),
+            allocated: TOTAL.load(Ordering::Relaxed) - self.total,
+            allocations: COUNT.load(Ordering::Relaxed) - self.count,
+        })
+    }
+}
+
+/// Stops measuring for good, a part that timed out keeps allocating on a thread nobody waits for.
+pub fn abandon() {
+    ABANDONED.store(true, Ordering::Relaxed);
+}
+
+/// A byte count in the largest binary unit that keeps it above one.
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    #[cfg(feature = "memory")]
+    fn test_measure() {
+        let measure = Measure::start();
+        let buffer = vec![0u8; 1 << 20];
+        drop(buffer);
+        let usage = measure.finish().unwrap();
+
+        // other tests allocate at the same time, so only lower bounds hold
+        assert!(usage.peak >= 1 << 20);
+        assert!(usage.allocated >= 1 << 20);
+        assert!(usage.allocations >= 1);
+
+        // nothing else measures, so the tests can do without measurements from here on
+        let measure = Measure::start();
+        abandon();
+        assert_eq!(measure.finish(), None);
+    }
+}
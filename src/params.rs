@@ -0,0 +1,91 @@
+use std::{collections::BTreeMap, fmt::{self, Display}, str::FromStr};
+
+use crate::error::ParseError;
+
+/// Values a solution depends on that are not part of its input, like the size of a grid.
+///
+/// A day declares its parameters in `Solution::PARAMS` with the values for the real puzzle,
+/// they can be overridden in `aoc.toml`, with `--param name=value` or by an example.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params(BTreeMap<String, String>);
+
+impl Params {
+    pub fn new(defaults: &[(&str, &str)]) -> Params {
+        Params(defaults.iter().map(|&(name, value)| (name.to_string(), value.to_string())).collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.0.contains_key(name)
+    }
+
+    /// Overrides a parameter, only the ones the day declares can be set.
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        if let Some(current) = self.0.get_mut(name) {
+            *current = value.to_string();
+            return Ok(());
+        }
+
+        if self.is_empty() {
+            Err(format!("Unknown parameter {}, there are none", name))
+        } else {
+            Err(format!("Unknown parameter {}, expected one of {}", name, self.names().join(", ")))
+        }
+    }
+
+    /// Overrides several parameters at once.
+    pub fn with(mut self, values: &[(&str, &str)]) -> Result<Params, String> {
+        for &(name, value) in values {
+            self.set(name, value)?;
+        }
+
+        Ok(self)
+    }
+
+    pub fn get<T: FromStr>(&self, name: &str) -> Result<T, ParseError> {
+        let value = self.0.get(name).ok_or_else(|| ParseError::new(format!("Missing parameter {}", name)))?;
+
+        value.parse().map_err(|_| ParseError::new(format!("Invalid value for parameter {}: {}", name, value)))
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.0.keys().map(String::as_str).collect()
+    }
+}
+
+impl Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values: Vec<String> = self.0.iter().map(|(name, value)| format!("{}={}", name, value)).collect();
+
+        write!(f, "{}", values.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params() {
+        let params = Params::new(&[("width", "101"), ("height", "103")]).with(&[("width", "11")]).unwrap();
+
+        assert_eq!(params.get::<usize>("width"), Ok(11));
+        assert_eq!(params.get::<i64>("height"), Ok(103));
+        assert_eq!(params.to_string(), "height=103, width=11");
+        assert!(params.get::<usize>("depth").is_err());
+    }
+
+    #[test]
+    fn test_set_errors() {
+        let mut params = Params::new(&[("width", "101")]);
+
+        assert_eq!(params.set("depth", "3"), Err("Unknown parameter depth, expected one of width".to_string()));
+        assert!(Params::default().set("width", "3").is_err());
+
+        params.set("width", "wide").unwrap();
+        assert!(params.get::<usize>("width").is_err());
+    }
+}
@@ -0,0 +1,356 @@
+use std::{fmt::Write, time::Duration};
+
+use colored::{ColoredString, Colorize};
+use serde::Serialize;
+
+use crate::{answers::Verdict, cli::Format, error::ParseError, memory::{self, Usage}, solution::Answer, worker::Failure, Day};
+
+/// The outcome of running a single part of a day.
+pub struct PartResult {
+    pub part: u8,
+    /// The answer, or why the part did not finish.
+    pub answer: Result<Answer, Failure>,
+    pub verdict: Verdict,
+    pub duration: Duration,
+    /// Heap usage, only measured with the `memory` feature.
+    pub memory: Option<Usage>,
+}
+
+/// The outcome of running a day: either its parts, or the reason the input could not be parsed.
+pub struct DayResult {
+    pub day: usize,
+    /// Which of several inputs of the day this ran on, like `alice` or `example 2`.
+    pub input: Option<String>,
+    pub parse_time: Duration,
+    pub outcome: Result<Vec<PartResult>, ParseError>,
+}
+
+impl DayResult {
+    /// Whether the input parsed, every part finished and no answer contradicts the stored ones.
+    pub fn passed(&self) -> bool {
+        match &self.outcome {
+            Ok(parts) => parts.iter().all(|p| p.answer.is_ok() && !matches!(p.verdict, Verdict::Fail { .. })),
+            Err(_) => false,
+        }
+    }
+}
+
+struct Row {
+    day: String,
+    part: &'static str,
+    answer: String,
+    verdict: Option<Verdict>,
+    time: String,
+    /// The peak heap usage, empty when it was not measured.
+    peak: String,
+    failed: bool,
+}
+
+/// Prints a table of all results followed by the days that had to be skipped.
+pub fn print_summary(results: &[DayResult], skipped: &[&Day]) {
+    let mut rows = Vec::new();
+    let mut total = Duration::ZERO;
+
+    for result in results {
+        let day = match &result.input {
+            Some(input) => format!("{:0>2} {}", result.day, input),
+            None => format!("{:0>2}", result.day),
+        };
+
+        total += result.parse_time;
+        rows.push(Row {
+            day: day.clone(),
+            part: "parse",
+            answer: match &result.outcome {
+                Ok(_) => String::new(),
+                Err(e) => format!("parse error: {}", e),
+            },
+            verdict: None,
+            time: format!("{:.2?}", result.parse_time),
+            peak: String::new(),
+            failed: result.outcome.is_err(),
+        });
+
+        for part in result.outcome.iter().flatten() {
+            total += part.duration;
+            rows.push(Row {
+                day: day.clone(),
+                part: if part.part == 1 { "1" } else { "2" },
+                answer: match &part.answer {
+                    Ok(answer) => answer.to_string().replace('\n', " "),
+                    Err(failure) => failure.to_string(),
+                },
+                verdict: part.answer.is_ok().then(|| part.verdict.clone()),
+                time: format!("{:.2?}", part.duration),
+                peak: part.memory.map(|usage| memory::format_bytes(usage.peak)).unwrap_or_default(),
+                failed: part.answer.is_err(),
+            });
+        }
+    }
+
+    let total = format!("{:.2?}", total);
+    let day_width = rows.iter().map(|r| r.day.chars().count()).chain([3]).max().unwrap();
+    let answer_width = rows.iter().map(|r| r.answer.chars().count()).chain([6]).max().unwrap();
+    let check_width = rows.iter().flat_map(|r| &r.verdict).map(|v| v.to_string().chars().count()).chain([5]).max().unwrap();
+    let time_width = rows.iter().map(|r| r.time.len()).chain([total.len(), 4]).max().unwrap();
+    // the memory column only shows up when something was measured
+    let peak_width = rows.iter().map(|r| r.peak.len()).max().filter(|&w| w > 0).map_or(0, |w| w.max(4));
+    let peak = |text: &str| if peak_width > 0 { format!("  {:>peak_width$}", text) } else { String::new() };
+    let rule = "─".repeat(day_width + 2 + 5 + 2 + answer_width + 2 + check_width + 2 + time_width + peak("").len());
+
+    println!("{}", "Summary".bold());
+    println!("{}", rule);
+    println!("{:<day_width$}  {:<5}  {:<answer_width$}  {:<check_width$}  {:>time_width$}{}", "Day", "Part", "Answer", "Check", "Time", peak("Peak"));
+    println!("{}", rule);
+
+    for row in rows {
+        let answer = format!("{:<answer_width$}", row.answer);
+        let answer: ColoredString = if row.failed { answer.red() } else { answer.green() };
+        let check = match &row.verdict {
+            // pad before colouring, the escape codes would otherwise count towards the width
+            Some(verdict) => {
+                let padding = " ".repeat(check_width - verdict.to_string().chars().count());
+                format!("{}{}", verdict.colored(), padding)
+            }
+            None => " ".repeat(check_width),
+        };
+        println!(
+            "{:<day_width$}  {:<5}  {}  {}  {}{}",
+            row.day,
+            row.part,
+            answer,
+            check,
+            format!("{:>time_width$}", row.time).dimmed(),
+            peak(&row.peak).dimmed(),
+        );
+    }
+
+    println!("{}", rule);
+    println!("{:<day_width$}  {:<5}  {:<answer_width$}  {:<check_width$}  {:>time_width$}", "", "", "Total", "", total);
+
+    if !skipped.is_empty() {
+        let skipped: Vec<String> = skipped.iter().map(|d| d.to_string()).collect();
+        println!("\n{} {}", "Skipped (no input):".yellow(), skipped.join(", "));
+    }
+    println!();
+}
+
+/// One line of the machine-readable output: the parse step, a part or a skipped day.
+#[derive(Debug, PartialEq, Serialize)]
+struct Record {
+    day: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input: Option<String>,
+    part: String,
+    answer: String,
+    status: &'static str,
+    expected: Option<String>,
+    duration_ns: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    memory: Option<Usage>,
+}
+
+fn records(results: &[DayResult], skipped: &[&Day]) -> Vec<Record> {
+    let mut records = Vec::new();
+
+    for result in results {
+        records.push(Record {
+            day: result.day,
+            input: result.input.clone(),
+            part: "parse".to_string(),
+            answer: result.outcome.as_ref().err().map(ToString::to_string).unwrap_or_default(),
+            status: if result.outcome.is_ok() { "ok" } else { "error" },
+            expected: None,
+            duration_ns: Some(result.parse_time.as_nanos() as u64),
+            memory: None,
+        });
+
+        for part in result.outcome.iter().flatten() {
+            let (status, expected) = match (&part.answer, &part.verdict) {
+                (Err(Failure::Panic(_)), _) => ("panic", None),
+                (Err(Failure::Timeout(_)), _) => ("timeout", None),
+                (Ok(_), Verdict::Pass) => ("pass", None),
+                (Ok(_), Verdict::Fail { expected }) => ("fail", Some(expected.clone())),
+                (Ok(_), Verdict::Unknown) => ("unknown", None),
+            };
+            records.push(Record {
+                day: result.day,
+                input: result.input.clone(),
+                part: part.part.to_string(),
+                answer: match &part.answer {
+                    Ok(answer) => answer.to_string(),
+                    Err(failure) => failure.to_string(),
+                },
+                status,
+                expected,
+                duration_ns: Some(part.duration.as_nanos() as u64),
+                memory: part.memory,
+            });
+        }
+    }
+
+    for day in skipped {
+        records.push(Record {
+            day: day.num,
+            input: None,
+            part: String::new(),
+            answer: String::new(),
+            status: "skipped",
+            expected: None,
+            duration_ns: None,
+            memory: None,
+        });
+    }
+
+    records
+}
+
+/// Quotes a CSV field when it holds a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Renders the results without any colour codes, ready to be written to stdout or a file.
+pub fn render(format: Format, results: &[DayResult], skipped: &[&Day]) -> Result<String, String> {
+    let records = records(results, skipped);
+    let mut out = String::new();
+
+    match format {
+        Format::Json => {
+            out = serde_json::to_string_pretty(&records).map_err(|e| format!("Could not serialize results: {}", e))?;
+            out.push('\n');
+        }
+        Format::Csv => {
+            out.push_str("day,input,part,answer,status,expected,duration_ns,peak_bytes,allocated_bytes,allocations\n");
+            for r in &records {
+                let memory = |field: fn(&Usage) -> usize| r.memory.as_ref().map(|m| field(m).to_string()).unwrap_or_default();
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{},{},{},{},{},{}",
+                    r.day,
+                    csv_field(r.input.as_deref().unwrap_or("")),
+                    r.part,
+                    csv_field(&r.answer),
+                    r.status,
+                    csv_field(r.expected.as_deref().unwrap_or("")),
+                    r.duration_ns.map(|ns| ns.to_string()).unwrap_or_default(),
+                    memory(|m| m.peak),
+                    memory(|m| m.allocated),
+                    memory(|m| m.allocations),
+                );
+            }
+        }
+        Format::Plain => {
+            for r in &records {
+                let _ = write!(out, "Day {:0>2}", r.day);
+                if let Some(input) = &r.input {
+                    let _ = write!(out, " ({})", input);
+                }
+                match r.part.as_str() {
+                    "" => {}
+                    "parse" => out.push_str(" parse"),
+                    part => {
+                        let _ = write!(out, " part {}", part);
+                    }
+                }
+                if !r.answer.is_empty() {
+                    let _ = write!(out, ": {}", r.answer.replace('\n', " "));
+                }
+                let _ = write!(out, " [{}", r.status);
+                if let Some(expected) = &r.expected {
+                    let _ = write!(out, ", expected {}", expected);
+                }
+                if let Some(ns) = r.duration_ns {
+                    let _ = write!(out, ", {:.2?}", Duration::from_nanos(ns));
+                }
+                if let Some(usage) = &r.memory {
+                    let _ = write!(out, ", peak {}", memory::format_bytes(usage.peak));
+                }
+                out.push_str("]\n");
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Vec<DayResult> {
+        let memory = Some(Usage { peak: 2048, allocated: 4096, allocations: 3 });
+        let part = |part, answer: Answer, verdict| PartResult { part, answer: Ok(answer), verdict, duration: Duration::from_nanos(30), memory };
+
+        vec![
+            DayResult {
+                day: 3,
+                input: None,
+                parse_time: Duration::from_nanos(10),
+                outcome: Ok(vec![
+                    part(1, Answer::Int(42), Verdict::Pass),
+                    part(2, "co,de".into(), Verdict::Fail { expected: "ab".to_string() }),
+                ]),
+            },
+            DayResult { day: 4, input: Some("alice".to_string()), parse_time: Duration::from_nanos(5), outcome: Err(ParseError::new("bad")) },
+        ]
+    }
+
+    #[test]
+    fn test_render_csv() {
+        let expected = "day,input,part,answer,status,expected,duration_ns,peak_bytes,allocated_bytes,allocations\n\
+                        3,,parse,,ok,,10,,,\n\
+                        3,,1,42,pass,,30,2048,4096,3\n\
+                        3,,2,\"co,de\",fail,ab,30,2048,4096,3\n\
+                        4,alice,parse,bad,error,,5,,,\n";
+
+        assert_eq!(render(Format::Csv, &results(), &[]).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_render_json() {
+        let json: serde_json::Value = serde_json::from_str(&render(Format::Json, &results(), &[]).unwrap()).unwrap();
+
+        assert_eq!(json[2]["answer"], "co,de");
+        assert_eq!(json[2]["expected"], "ab");
+        assert_eq!(json[3]["status"], "error");
+        assert_eq!(json[3]["input"], "alice");
+        assert_eq!(json[1]["memory"]["peak"], 2048);
+        assert!(json[0].get("input").is_none());
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let plain = render(Format::Plain, &results(), &[]).unwrap();
+
+        assert_eq!(plain.lines().nth(2), Some("Day 03 part 2: co,de [fail, expected ab, 30.00ns, peak 2.0 KiB]"));
+        assert_eq!(plain.lines().nth(3), Some("Day 04 (alice) parse: bad [error, 5.00ns]"));
+        assert!(!plain.contains('\u{1b}'));
+    }
+
+    #[test]
+    fn test_failed_part() {
+        let failed = DayResult {
+            day: 14,
+            input: None,
+            parse_time: Duration::from_nanos(10),
+            outcome: Ok(vec![PartResult {
+                part: 2,
+                answer: Err(Failure::Panic("No solution found".to_string())),
+                verdict: Verdict::Unknown,
+                duration: Duration::from_nanos(20),
+                memory: None,
+            }]),
+        };
+
+        assert!(!failed.passed());
+        assert_eq!(
+            render(Format::Plain, &[failed], &[]).unwrap().lines().nth(1),
+            Some("Day 14 part 2: panicked: No solution found [panic, 20.00ns]")
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Generic graph searches over any state type: the caller describes the graph with a closure
+//! returning the neighbours of a state (and the cost to reach them for the weighted searches).
+
+use std::{cmp::Reverse, collections::{BinaryHeap, HashMap, HashSet, VecDeque}, hash::Hash, ops::Add};
+
+/// A cost usable by the weighted searches, `Default` has to be zero.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+/// A goal that was reached, with the cost and every state from the start to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+}
+
+/// Follows `parents` back from `end`, returning the path in walking order.
+pub fn reconstruct_path<S: Clone + Eq + Hash>(parents: &HashMap<S, S>, end: &S) -> Vec<S> {
+    let mut path = vec![end.clone()];
+
+    while let Some(parent) = parents.get(path.last().unwrap()) {
+        path.push(parent.clone());
+    }
+
+    path.reverse();
+    path
+}
+
+/// Breadth-first search for the closest state satisfying `is_goal`, every step costs one.
+pub fn bfs<S, I>(start: S, mut neighbours: impl FnMut(&S) -> I, mut is_goal: impl FnMut(&S) -> bool) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut parents = HashMap::new();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((state, cost)) = queue.pop_front() {
+        if is_goal(&state) {
+            return Some(Found { cost, path: reconstruct_path(&parents, &state) });
+        }
+
+        for next in neighbours(&state) {
+            if seen.insert(next.clone()) {
+                parents.insert(next.clone(), state.clone());
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// The number of steps to every state reachable from `start`.
+pub fn bfs_distances<S, I>(start: S, mut neighbours: impl FnMut(&S) -> I) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::from([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(state) = queue.pop_front() {
+        let distance = distances[&state];
+
+        for next in neighbours(&state) {
+            distances.entry(next).or_insert_with_key(|next| {
+                queue.push_back(next.clone());
+                distance + 1
+            });
+        }
+    }
+
+    distances
+}
+
+/// Dijkstra's algorithm, the cheapest path to a state satisfying `is_goal`.
+pub fn dijkstra<S, C, I>(start: S, neighbours: impl FnMut(&S) -> I, is_goal: impl FnMut(&S) -> bool) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search, `heuristic` must never overestimate the remaining cost for the result to be optimal.
+pub fn astar<S, C, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    let mut parents = HashMap::new();
+    // the heap holds indices into `states` so the states themselves do not need to be ordered
+    let mut states = vec![start.clone()];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        let state = states[index].clone();
+        if costs.get(&state).is_some_and(|&best| cost > best) {
+            continue;
+        }
+
+        if is_goal(&state) {
+            return Some(Found { cost, path: reconstruct_path(&parents, &state) });
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if costs.get(&next).is_some_and(|&best| best <= next_cost) {
+                continue;
+            }
+
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), state.clone());
+            heap.push(Reverse((next_cost + heuristic(&next), next_cost, states.len())));
+            states.push(next);
+        }
+    }
+
+    None
+}
+
+/// Every state lying on at least one cheapest path from `start` to any goal, with that cost.
+pub fn optimal_states<S, C, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<(C, HashSet<S>)>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut costs = HashMap::from([(start.clone(), C::default())]);
+    // unlike a plain search, every predecessor reaching a state at its best cost is kept
+    let mut parents: HashMap<S, Vec<S>> = HashMap::new();
+    let mut states = vec![start];
+    let mut heap = BinaryHeap::from([Reverse((C::default(), 0))]);
+    let mut best: Option<C> = None;
+    let mut goals = Vec::new();
+
+    while let Some(Reverse((cost, index))) = heap.pop() {
+        if best.is_some_and(|best| cost > best) {
+            break;
+        }
+
+        let state = states[index].clone();
+        if costs.get(&state).is_some_and(|&c| cost > c) {
+            continue;
+        }
+
+        if is_goal(&state) {
+            best = Some(cost);
+            goals.push(state);
+            continue;
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            match costs.get(&next) {
+                Some(&c) if c < next_cost => continue,
+                Some(&c) if c == next_cost => {
+                    parents.entry(next).or_default().push(state.clone());
+                    continue;
+                }
+                _ => {}
+            }
+
+            costs.insert(next.clone(), next_cost);
+            parents.insert(next.clone(), vec![state.clone()]);
+            heap.push(Reverse((next_cost, states.len())));
+            states.push(next);
+        }
+    }
+
+    let mut on_path: HashSet<S> = goals.iter().cloned().collect();
+    let mut stack = goals;
+
+    while let Some(state) = stack.pop() {
+        for parent in parents.get(&state).into_iter().flatten() {
+            if on_path.insert(parent.clone()) {
+                stack.push(parent.clone());
+            }
+        }
+    }
+
+    best.map(|cost| (cost, on_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a small weighted graph with two equally cheap routes from 0 to 4 (via 1 and via 2)
+    fn edges(n: &u32) -> Vec<(u32, u32)> {
+        match n {
+            0 => vec![(1, 1), (2, 2), (3, 1)],
+            1 => vec![(4, 3)],
+            2 => vec![(4, 2)],
+            3 => vec![(4, 10)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let found = bfs(0, |&n| [n + 1, n * 2], |&n| n == 10).unwrap();
+
+        assert_eq!(found.cost, 5);
+        assert_eq!(found.path.first(), Some(&0));
+        assert_eq!(found.path.last(), Some(&10));
+        assert!(bfs(0u32, |&n| (n < 5).then_some(n + 1), |&n| n == 10).is_none());
+    }
+
+    #[test]
+    fn test_bfs_distances() {
+        let distances = bfs_distances(0u32, |&n| (n < 3).then_some(n + 1));
+
+        assert_eq!(distances.len(), 4);
+        assert_eq!(distances[&3], 3);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let found = dijkstra(0, edges, |&n| n == 4).unwrap();
+
+        assert_eq!(found.cost, 4);
+        assert_eq!(found.path.len(), 3);
+        assert_eq!(found.path.last(), Some(&4));
+        assert!(dijkstra(0, edges, |&n| n == 5).is_none());
+    }
+
+    #[test]
+    fn test_astar() {
+        // walking on a line with a perfect heuristic
+        let found = astar(0i64, |&n| [(n - 1, 1), (n + 1, 1)], |&n| (7 - n).abs(), |&n| n == 7).unwrap();
+
+        assert_eq!(found.cost, 7);
+        assert_eq!(found.path, (0..=7).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_optimal_states() {
+        let (cost, states) = optimal_states(0, edges, |&n| n == 4).unwrap();
+
+        assert_eq!(cost, 4);
+        assert_eq!(states, HashSet::from([0, 1, 2, 4]));
+    }
+
+    #[test]
+    fn test_reconstruct_path() {
+        let parents = HashMap::from([(3, 2), (2, 1)]);
+
+        assert_eq!(reconstruct_path(&parents, &3), [1, 2, 3]);
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::{self, Display};
+
+use crate::{context::Context, error::ParseError, params::Params};
+
+/// A day's puzzle: the input is parsed once and then shared by both parts.
+pub trait Solution {
+    type Input;
+
+    /// The parameters the day reads, with their values for the real puzzle input.
+    const PARAMS: &'static [(&'static str, &'static str)] = &[];
+
+    /// Parses with the values of `PARAMS` for the real puzzle input, the runner always passes
+    /// parameters so only the tests parse without them.
+    #[cfg(test)]
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Self::parse_with(input, &Params::new(Self::PARAMS))
+    }
+
+    /// Parses with the given parameters, days with `PARAMS` keep the values they need in the input.
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+
+    /// Runs part 1 with the runner's context, days with side effects like printing or writing
+    /// files use it instead of doing them directly.
+    fn part1_with(input: &Self::Input, _context: &Context) -> Answer {
+        Self::part1(input)
+    }
+
+    /// Runs part 2 with the runner's context, like `part1_with`.
+    fn part2_with(input: &Self::Input, _context: &Context) -> Answer {
+        Self::part2(input)
+    }
+}
+
+/// The result of a single part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Str(String),
+    /// For a part without an answer, like a puzzle input the solution cannot handle.
+    Unsolved,
+    /// For a part without a puzzle, like the second part of the last day.
+    NoPuzzle,
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Str(s) => write!(f, "{}", s),
+            Answer::Unsolved => write!(f, "unsolved"),
+            Answer::NoPuzzle => write!(f, "no puzzle"),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Self {
+        Answer::Int(n)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Self {
+        Answer::Int(n.try_into().expect("Answer is too large for i64"))
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        Answer::Int(n.try_into().expect("Answer is too large for i64"))
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Self {
+        Answer::Int(n.into())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+/// A parsed input with its parts, erased so every day fits in the same list. It is shared with
+/// the worker threads running the parts.
+pub trait Parsed: Send + Sync {
+    fn solve(&self, part: u8, context: &Context) -> Answer;
+}
+
+struct ParsedInput<S: Solution>(S::Input);
+
+impl<S: Solution> Parsed for ParsedInput<S> where S::Input: Send + Sync {
+    fn solve(&self, part: u8, context: &Context) -> Answer {
+        match part {
+            1 => S::part1_with(&self.0, context),
+            2 => S::part2_with(&self.0, context),
+            _ => unreachable!("there are only two parts"),
+        }
+    }
+}
+
+/// How the runner parses a day's input, whatever its `Solution::Input` is.
+pub type ParseFn = fn(&str, &Params) -> Result<Box<dyn Parsed>, ParseError>;
+
+pub fn parse<S: Solution + 'static>(input: &str, params: &Params) -> Result<Box<dyn Parsed>, ParseError>
+where
+    S::Input: Send + Sync,
+{
+    Ok(Box::new(ParsedInput::<S>(S::parse_with(input, params)?)))
+}
@@ -0,0 +1,85 @@
+use std::{any::Any, cell::Cell, fmt::{self, Display}, panic::{self, AssertUnwindSafe}, sync::{mpsc::{self, RecvTimeoutError}, Once}, thread, time::Duration};
+
+thread_local! {
+    static IN_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Keeps the default panic message for every thread but the workers, their panics are reported as failures.
+fn silence_worker_panics() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !IN_WORKER.get() {
+                default(info);
+            }
+        }));
+    });
+}
+
+/// Why a part did not produce an answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Panic(String),
+    Timeout(Duration),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Panic(message) => write!(f, "panicked: {}", message),
+            Failure::Timeout(limit) => write!(f, "timed out after {:?}", limit),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match (payload.downcast_ref::<&str>(), payload.downcast_ref::<String>()) {
+        (Some(message), _) => message.to_string(),
+        (_, Some(message)) => message.clone(),
+        _ => "unknown cause".to_string(),
+    }
+}
+
+/// Runs `f` on a thread of its own so a panic or running past `timeout` becomes a failure
+/// instead of taking the runner down. A thread that timed out cannot be stopped, it is left
+/// running in the background and keeps its CPU until `f` returns; parts with long loops check
+/// `Context::cancelled` so they give up once the runner cancels their context.
+pub fn run<T: Send + 'static>(name: String, timeout: Option<Duration>, f: impl FnOnce() -> T + Send + 'static) -> Result<T, Failure> {
+    silence_worker_panics();
+    let (sender, receiver) = mpsc::channel();
+
+    thread::Builder::new()
+        .name(name)
+        .spawn(move || {
+            IN_WORKER.set(true);
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+        })
+        .expect("Could not start a worker thread");
+
+    let result = match timeout {
+        Some(limit) => match receiver.recv_timeout(limit) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => return Err(Failure::Timeout(limit)),
+            Err(RecvTimeoutError::Disconnected) => unreachable!("the worker always sends its result"),
+        },
+        None => receiver.recv().expect("the worker always sends its result"),
+    };
+
+    result.map_err(|payload| Failure::Panic(panic_message(payload.as_ref())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        assert_eq!(run("ok".to_string(), None, || 42), Ok(42));
+        assert_eq!(run("panic".to_string(), None, || -> i32 { panic!("No solution found") }), Err(Failure::Panic("No solution found".to_string())));
+
+        let limit = Duration::from_millis(10);
+        assert_eq!(run("slow".to_string(), Some(limit), || thread::sleep(Duration::from_secs(1))), Err(Failure::Timeout(limit)));
+    }
+}